@@ -2,7 +2,7 @@
 
 from fastapi import APIRouter
 
-from .v1 import actions, agent, asset, boards, chat, query, settings, projects, models, source
+from .v1 import actions, agent, asset, boards, chat, query, settings, projects, models, source, system
 
 api_router = APIRouter()
 api_router.include_router(query.router, prefix="/api/v1/query", tags=["query"])
@@ -15,3 +15,4 @@ api_router.include_router(projects.router, prefix="/api/v1/projects", tags=["pro
 api_router.include_router(models.router, prefix="/api/v1/models", tags=["models"])
 api_router.include_router(source.router, prefix="/api/v1/source", tags=["source"])  # ATTACH + Cache
 api_router.include_router(asset.router, prefix="/api/v1/asset", tags=["asset"])  # Saved Analyses
+api_router.include_router(system.router, prefix="/api/v1/system", tags=["system"])
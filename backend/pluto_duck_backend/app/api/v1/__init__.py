@@ -1,6 +1,6 @@
 """API v1 package."""
 
-from . import actions, agent, asset, boards, chat, models, query, settings, projects, source
+from . import actions, agent, asset, boards, chat, models, query, settings, projects, source, system
 
 __all__ = [
     "actions",
@@ -13,5 +13,6 @@ __all__ = [
     "settings",
     "projects",
     "source",
+    "system",
 ]
 
@@ -0,0 +1,5 @@
+"""System maintenance API package."""
+
+from .router import router
+
+__all__ = ["router"]
@@ -0,0 +1,87 @@
+"""Maintenance endpoints the desktop shell calls, e.g. before backing up the data root or to
+hand memory back after a large query."""
+
+from __future__ import annotations
+
+import ctypes
+import ctypes.util
+import gc
+import logging
+import sys
+from pathlib import Path
+from typing import List
+
+from fastapi import APIRouter
+from pydantic import BaseModel
+
+from pluto_duck_backend.app.core.config import get_settings
+from pluto_duck_backend.app.services.duckdb_utils import connect_warehouse
+
+logger = logging.getLogger(__name__)
+
+router = APIRouter(tags=["system"])
+
+
+class CheckpointResponse(BaseModel):
+    checkpointed: List[str]
+    failed: List[str]
+
+
+class TrimMemoryResponse(BaseModel):
+    collected: int
+    released: bool
+
+
+def _warehouse_files() -> List[Path]:
+    """The main warehouse plus every per-project one under the data root."""
+
+    settings = get_settings()
+    paths = [settings.duckdb.path]
+    projects_dir = settings.data_dir.root / "data" / "projects"
+    if projects_dir.is_dir():
+        paths.extend(sorted(projects_dir.glob("*/warehouse.duckdb")))
+    return [path for path in paths if path.is_file()]
+
+
+@router.post("/checkpoint", response_model=CheckpointResponse)
+def checkpoint() -> CheckpointResponse:
+    """Flush each DuckDB write-ahead log into its database file so the files on disk are
+    consistent and safe to copy."""
+
+    checkpointed: List[str] = []
+    failed: List[str] = []
+    for path in _warehouse_files():
+        try:
+            with connect_warehouse(path) as con:
+                con.execute("CHECKPOINT")
+            checkpointed.append(str(path))
+        except Exception:
+            logger.exception("checkpoint failed for %s", path)
+            failed.append(str(path))
+    return CheckpointResponse(checkpointed=checkpointed, failed=failed)
+
+
+def _release_free_heap() -> bool:
+    """Hand freed allocator pages back to the OS where the C library allows it."""
+
+    try:
+        if sys.platform.startswith("linux"):
+            libc = ctypes.CDLL(ctypes.util.find_library("c"))
+            return bool(libc.malloc_trim(0))
+        if sys.platform == "darwin":
+            libc = ctypes.CDLL(ctypes.util.find_library("c"))
+            libc.malloc_zone_pressure_relief(None, 0)
+            return True
+    except (OSError, AttributeError):
+        logger.debug("releasing free heap is not supported here", exc_info=True)
+    return False
+
+
+@router.post("/trim-memory", response_model=TrimMemoryResponse)
+def trim_memory() -> TrimMemoryResponse:
+    """Run a full garbage collection and release the freed heap back to the OS."""
+
+    collected = gc.collect()
+    released = _release_free_heap()
+    logger.info("trimmed memory: %d objects collected, heap released: %s", collected, released)
+    return TrimMemoryResponse(collected=collected, released=released)
@@ -7,7 +7,11 @@ required directories exist before launching the FastAPI app with uvicorn.
 from __future__ import annotations
 
 import argparse
+import errno
+import json
 import os
+import socket
+import sys
 from pathlib import Path
 from typing import Optional
 
@@ -36,10 +40,43 @@ def _parse_args() -> argparse.Namespace:
         default=None,
         help="Override log level (e.g., INFO, DEBUG)",
     )
+    parser.add_argument("--ssl-certfile", default=None, help="Serve HTTPS with this certificate")
+    parser.add_argument("--ssl-keyfile", default=None, help="Private key for --ssl-certfile")
+    parser.add_argument(
+        "--inspect",
+        default=None,
+        metavar="HOST:PORT",
+        help="Start a debugpy listener so an IDE can attach (development only)",
+    )
     return parser.parse_args()
 
 
+def _load_secrets() -> None:
+    """Move secrets the desktop shell wrote to a private file into our own environment.
+
+    The file is deleted right away so the values only live in this process's memory,
+    not in a process listing or on disk.
+    """
+    path = os.environ.pop("PLUTODUCK_SECRETS_FILE", None)
+    if not path:
+        return
+    try:
+        with open(path, encoding="utf-8") as handle:
+            secrets = json.load(handle)
+    except (OSError, ValueError) as exc:
+        print(f"failed to read secrets file: {exc}", file=sys.stderr)
+        return
+    finally:
+        try:
+            os.remove(path)
+        except OSError:
+            pass
+    for name, value in secrets.items():
+        os.environ[str(name)] = str(value)
+
+
 def _prepare_environment(data_root: Path, log_level: Optional[str]) -> PlutoDuckSettings:
+    _load_secrets()
     os.environ.setdefault("PLUTODUCK_DATA_DIR__ROOT", str(data_root))
     if log_level:
         os.environ.setdefault("PLUTODUCK_LOG_LEVEL", log_level)
@@ -49,16 +86,63 @@ def _prepare_environment(data_root: Path, log_level: Optional[str]) -> PlutoDuck
     return settings
 
 
+def _start_debugger(address: str) -> None:
+    host, _, port = address.rpartition(":")
+    try:
+        import debugpy
+    except ImportError:
+        print("--inspect requested but debugpy is not installed; continuing without it", file=sys.stderr)
+        return
+    debugpy.listen((host or "127.0.0.1", int(port)))
+    print(f"debugpy listening on {host or '127.0.0.1'}:{port}", file=sys.stderr)
+
+
+def _bind(host: str, port: int) -> socket.socket:
+    """Bind the listening socket, falling back to a free port if ``port`` is taken."""
+    family = socket.AF_INET6 if ":" in host else socket.AF_INET
+    sock = socket.socket(family, socket.SOCK_STREAM)
+    if os.name != "nt":
+        sock.setsockopt(socket.SOL_SOCKET, socket.SO_REUSEADDR, 1)
+    try:
+        sock.bind((host.strip("[]"), port))
+    except OSError as exc:
+        if exc.errno not in (errno.EADDRINUSE, getattr(errno, "WSAEADDRINUSE", None)):
+            raise
+        print(f"port {port} is in use; binding a free port instead", file=sys.stderr)
+        sock.bind((host.strip("[]"), 0))
+    sock.set_inheritable(True)
+    return sock
+
+
+def _write_port_file(data_root: Path, port: int) -> None:
+    """Report the bound port to the desktop shell, which watches ``server.port``.
+
+    Written to a temporary file and renamed, so the shell never reads a partial number.
+    """
+    path = data_root / "server.port"
+    tmp = path.with_suffix(".port.tmp")
+    tmp.write_text(str(port), encoding="utf-8")
+    os.replace(tmp, path)
+
+
 def main() -> None:
     args = _parse_args()
-    settings = _prepare_environment(args.data_root.expanduser(), args.log_level)
-
-    uvicorn.run(
+    data_root = args.data_root.expanduser()
+    settings = _prepare_environment(data_root, args.log_level)
+    if args.inspect:
+        _start_debugger(args.inspect)
+
+    sock = _bind(args.host, args.port)
+    _write_port_file(data_root, sock.getsockname()[1])
+    config = uvicorn.Config(
         "pluto_duck_backend.app.main:app",
         host=args.host,
-        port=args.port,
+        port=sock.getsockname()[1],
         log_level=settings.log_level.lower(),
+        ssl_certfile=args.ssl_certfile,
+        ssl_keyfile=args.ssl_keyfile,
     )
+    uvicorn.Server(config).run(sockets=[sock])
 
 
 if __name__ == "__main__":
@@ -0,0 +1,49 @@
+from __future__ import annotations
+
+import duckdb
+from fastapi import FastAPI
+from fastapi.testclient import TestClient
+
+from pluto_duck_backend.app.api.router import api_router
+from pluto_duck_backend.app.core.config import get_settings
+
+
+def create_client(tmp_path, monkeypatch) -> TestClient:
+    monkeypatch.setenv("PLUTODUCK_DATA_DIR__ROOT", str(tmp_path / "root"))
+    get_settings.cache_clear()
+    app = FastAPI()
+    app.include_router(api_router)
+    return TestClient(app)
+
+
+def test_checkpoint_flushes_existing_warehouses(tmp_path, monkeypatch) -> None:
+    client = create_client(tmp_path, monkeypatch)
+    warehouse = get_settings().duckdb.path
+    con = duckdb.connect(str(warehouse))
+    con.execute("CREATE TABLE t AS SELECT 1 AS x")
+    con.close()
+
+    response = client.post("/api/v1/system/checkpoint")
+
+    assert response.status_code == 200
+    assert response.json() == {"checkpointed": [str(warehouse)], "failed": []}
+
+
+def test_checkpoint_without_warehouse_is_a_no_op(tmp_path, monkeypatch) -> None:
+    client = create_client(tmp_path, monkeypatch)
+
+    response = client.post("/api/v1/system/checkpoint")
+
+    assert response.status_code == 200
+    assert response.json() == {"checkpointed": [], "failed": []}
+
+
+def test_trim_memory_reports_collection(tmp_path, monkeypatch) -> None:
+    client = create_client(tmp_path, monkeypatch)
+
+    response = client.post("/api/v1/system/trim-memory")
+
+    assert response.status_code == 200
+    body = response.json()
+    assert body["collected"] >= 0
+    assert isinstance(body["released"], bool)
@@ -19,7 +19,14 @@ const WEB_CALLBACK_PATH = '/auth/callback';
 const DEFAULT_TAURI_CALLBACK_URL = 'plutoduck://auth/callback';
 const TAURI_BRIDGE_FLAG = 'tauri_bridge';
 
-type CallbackEventPayload = { url?: string } | string;
+type CallbackEventPayload = { id?: string; url?: string } | string;
+
+/** A callback the desktop shell holds until it is acknowledged with `ack_deeplink`. */
+type PendingAuthCallback = { id: string; url: string };
+
+// The shell re-delivers unacknowledged callbacks on every mount; ids already taken stay here
+// so an in-flight one isn't exchanged twice.
+const handledCallbackIds = new Set<string>();
 
 declare global {
   interface Window {
@@ -141,6 +148,16 @@ async function openInDefaultBrowser(url: string): Promise<void> {
   await invoke('open_external_url', { url });
 }
 
+async function fetchPendingAuthCallbacks(): Promise<PendingAuthCallback[]> {
+  const { invoke } = await import('@tauri-apps/api/core');
+  return invoke<PendingAuthCallback[]>('pending_auth_callbacks');
+}
+
+async function ackAuthCallback(id: string): Promise<void> {
+  const { invoke } = await import('@tauri-apps/api/core');
+  await invoke('ack_deeplink', { id });
+}
+
 type AuthContextValue = {
   session: Session | null;
   user: User | null;
@@ -325,6 +342,21 @@ export function AuthProvider({ children }: { children: ReactNode }) {
     });
     subscription = nextSubscription;
 
+    const handleCallback = (url: string, id: string | null) => {
+      if (id) {
+        if (handledCallbackIds.has(id)) return;
+        handledCallbackIds.add(id);
+      }
+      void finishOAuthCallback(url)
+        .catch(() => {
+          // Error state is already set in finishOAuthCallback.
+        })
+        .finally(() => {
+          // A code is single-use, so a failed exchange is acknowledged too.
+          if (id) void ackAuthCallback(id).catch(() => {});
+        });
+    };
+
     const handleWindowCallbackEvent = (event: Event) => {
       const customEvent = event as CustomEvent<CallbackEventPayload>;
       const detail = customEvent.detail;
@@ -335,20 +367,30 @@ export function AuthProvider({ children }: { children: ReactNode }) {
           ? readString(detail.url)
           : null;
       if (!url) return;
-      void finishOAuthCallback(url).catch(() => {
-        // Error state is already set in finishOAuthCallback.
-      });
+      const id = detail && typeof detail === 'object' ? readString(detail.id) : null;
+      handleCallback(url, id);
     };
 
     if (typeof window !== 'undefined') {
       const queue = window.__plutoAuthCallbackQueue ?? [];
-      if (queue.length > 0) {
-        const pending = [...queue];
-        window.__plutoAuthCallbackQueue = [];
-        for (const url of pending) {
-          void finishOAuthCallback(url).catch(() => {
-            // Error state is already set in finishOAuthCallback.
+      window.__plutoAuthCallbackQueue = [];
+      if (isTauriRuntime()) {
+        // The shell's queue holds the same URLs with ids and outlives reloads, so it wins.
+        void fetchPendingAuthCallbacks()
+          .then((pending) => {
+            if (!mounted) return;
+            for (const callback of pending) {
+              handleCallback(callback.url, callback.id);
+            }
+          })
+          .catch(() => {
+            for (const url of queue) {
+              handleCallback(url, null);
+            }
           });
+      } else {
+        for (const url of queue) {
+          handleCallback(url, null);
         }
       }
 
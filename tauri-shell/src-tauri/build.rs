@@ -1,3 +1,63 @@
+use std::path::Path;
+use std::process::Command;
+use std::time::{SystemTime, UNIX_EPOCH};
+
 fn main() {
+  // Recorded for the `build_info` command. CI passes GIT_HASH; local builds ask git.
+  println!("cargo:rerun-if-env-changed=GIT_HASH");
+  println!("cargo:rerun-if-env-changed=SOURCE_DATE_EPOCH");
+  watch_git_head(Path::new("../../.git"));
+  let commit = std::env::var("GIT_HASH")
+    .ok()
+    .filter(|hash| !hash.trim().is_empty())
+    .or_else(git_commit)
+    .unwrap_or_else(|| "unknown".to_string());
+  // Honour SOURCE_DATE_EPOCH for reproducible builds.
+  let built_at = std::env::var("SOURCE_DATE_EPOCH")
+    .ok()
+    .and_then(|epoch| epoch.trim().parse::<u64>().ok())
+    .unwrap_or_else(|| {
+      SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_secs())
+        .unwrap_or(0)
+    });
+  println!("cargo:rustc-env=PLUTODUCK_GIT_HASH={}", commit.trim());
+  println!("cargo:rustc-env=PLUTODUCK_BUILD_TIMESTAMP={built_at}");
+  println!(
+    "cargo:rustc-env=PLUTODUCK_BUILD_TARGET={}",
+    std::env::var("TARGET").unwrap_or_default()
+  );
+
   tauri_build::build()
 }
+
+/// Reruns the script when HEAD moves, so the hash doesn't go stale on incremental builds:
+/// `HEAD` for a checkout or branch switch, and the branch it points to for a new commit.
+/// Missing files are skipped, since cargo would otherwise rerun on every build.
+fn watch_git_head(git_dir: &Path) {
+  let head = git_dir.join("HEAD");
+  let Ok(contents) = std::fs::read_to_string(&head) else {
+    return;
+  };
+  println!("cargo:rerun-if-changed={}", head.display());
+  if let Some(reference) = contents.trim().strip_prefix("ref: ") {
+    // A branch only lives in `packed-refs` until its next commit.
+    for path in [git_dir.join(reference), git_dir.join("packed-refs")] {
+      if path.exists() {
+        println!("cargo:rerun-if-changed={}", path.display());
+      }
+    }
+  }
+}
+
+fn git_commit() -> Option<String> {
+  let output = Command::new("git")
+    .args(["rev-parse", "--short=12", "HEAD"])
+    .output()
+    .ok()?;
+  output
+    .status
+    .success()
+    .then(|| String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
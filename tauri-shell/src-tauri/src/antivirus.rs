@@ -0,0 +1,107 @@
+//! Recognizes antivirus interference on Windows, where a quarantined or locked backend binary
+//! fails to spawn with an access or sharing error that otherwise reads like a broken install.
+
+use std::fs::File;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+use tauri::AppHandle;
+
+use crate::backend;
+
+/// The backend binary exists but Windows refused to run it, most likely because antivirus
+/// software quarantined or locked it. Shown to the user instead of a generic spawn failure.
+#[derive(Debug)]
+pub struct BlockedByAntivirus {
+  pub path: PathBuf,
+  pub reason: String,
+  pub log_dir: PathBuf,
+}
+
+impl std::fmt::Display for BlockedByAntivirus {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    write!(
+      f,
+      "Windows blocked the Pluto Duck server from starting ({}: {}). This is usually antivirus software quarantining or locking the file; allow Pluto Duck in your antivirus and start it again. Logs are in {}.",
+      self.path.display(),
+      self.reason,
+      self.log_dir.display()
+    )
+  }
+}
+
+impl std::error::Error for BlockedByAntivirus {}
+
+/// Access denied, sharing and lock violations, and the two "virus" codes Defender reports.
+#[cfg(windows)]
+fn suggests_antivirus(err: &std::io::Error) -> bool {
+  use windows_sys::Win32::Foundation::{
+    ERROR_ACCESS_DENIED, ERROR_LOCK_VIOLATION, ERROR_SHARING_VIOLATION, ERROR_VIRUS_DELETED,
+    ERROR_VIRUS_INFECTED,
+  };
+
+  let Some(code) = err.raw_os_error().and_then(|code| u32::try_from(code).ok()) else {
+    return false;
+  };
+  matches!(
+    code,
+    ERROR_ACCESS_DENIED
+      | ERROR_SHARING_VIOLATION
+      | ERROR_LOCK_VIOLATION
+      | ERROR_VIRUS_INFECTED
+      | ERROR_VIRUS_DELETED
+  )
+}
+
+#[cfg(not(windows))]
+fn suggests_antivirus(_err: &std::io::Error) -> bool {
+  false
+}
+
+/// Wraps a failed spawn of `binary`, as [`BlockedByAntivirus`] when the error looks like it.
+pub fn spawn_error(binary: &Path, log_dir: &Path, err: std::io::Error) -> anyhow::Error {
+  if suggests_antivirus(&err) {
+    log::error!("spawning {} failed in a way that suggests antivirus: {err}", binary.display());
+    return BlockedByAntivirus {
+      path: binary.to_path_buf(),
+      reason: err.to_string(),
+      log_dir: log_dir.to_path_buf(),
+    }
+    .into();
+  }
+  anyhow::Error::new(err).context("failed to spawn backend process")
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FileAccessReport {
+  pub path: PathBuf,
+  pub exists: bool,
+  pub accessible: bool,
+  pub error: Option<String>,
+  /// The error is one antivirus software typically causes; always false outside Windows.
+  pub suspected_antivirus: bool,
+}
+
+/// Opens `path` (by default the backend binary) and reads a byte, to tell whether something
+/// has it locked or quarantined without trying to start it.
+#[tauri::command]
+pub fn check_file_accessible(
+  app: AppHandle,
+  path: Option<PathBuf>,
+) -> Result<FileAccessReport, String> {
+  let path = match path {
+    Some(path) => path,
+    None => backend::backend_binary_path(&app).map_err(|err| err.to_string())?,
+  };
+  let result = File::open(&path).and_then(|mut file| file.read(&mut [0u8; 1]));
+  let error = result.err();
+  Ok(FileAccessReport {
+    exists: path.exists(),
+    accessible: error.is_none(),
+    suspected_antivirus: error.as_ref().is_some_and(suggests_antivirus),
+    error: error.map(|err| err.to_string()),
+    path,
+  })
+}
@@ -0,0 +1,48 @@
+//! HTTP calls from the shell to the local backend.
+
+use std::time::Duration;
+
+use crate::{backend, tls};
+
+/// Proxies are bypassed since the backend only ever listens on 127.0.0.1. Over local TLS the
+/// shell's own CA is the one root trusted.
+fn builder() -> reqwest::ClientBuilder {
+  let builder = reqwest::Client::builder().no_proxy();
+  match tls::root_certificate() {
+    Some(ca) => builder.tls_built_in_root_certs(false).add_root_certificate(ca),
+    None => builder,
+  }
+}
+
+/// Client for talking to the loopback backend.
+pub fn client(timeout: Duration) -> Result<reqwest::Client, String> {
+  builder()
+    .timeout(timeout)
+    .build()
+    .map_err(|err| format!("Failed to build HTTP client: {err}"))
+}
+
+/// Client for long transfers such as exports: no overall deadline, but a connection that goes
+/// quiet for `idle_timeout` still fails.
+pub fn streaming_client(idle_timeout: Duration) -> Result<reqwest::Client, String> {
+  builder()
+    .connect_timeout(idle_timeout)
+    .read_timeout(idle_timeout)
+    .build()
+    .map_err(|err| format!("Failed to build HTTP client: {err}"))
+}
+
+pub fn url(path: &str) -> String {
+  format!("{}{path}", backend::origin())
+}
+
+/// POSTs to a backend endpoint and fails on transport errors or non-success statuses.
+pub async fn post(path: &str, timeout: Duration) -> Result<reqwest::Response, String> {
+  client(timeout)?
+    .post(url(path))
+    .send()
+    .await
+    .map_err(|err| format!("Backend request to {path} failed: {err}"))?
+    .error_for_status()
+    .map_err(|err| format!("Backend rejected {path}: {err}"))
+}
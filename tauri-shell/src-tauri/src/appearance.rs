@@ -0,0 +1,95 @@
+use tauri::window::Color;
+use tauri::{Manager, State, WebviewWindow};
+
+use crate::preferences::{self, Preferences};
+
+/// Parses `#RRGGBB` or `#RRGGBBAA` (the leading `#` is optional).
+pub fn parse_hex_color(hex: &str) -> Result<Color, String> {
+  let digits = hex.trim().trim_start_matches('#');
+  if !matches!(digits.len(), 6 | 8) || !digits.bytes().all(|byte| byte.is_ascii_hexdigit()) {
+    return Err(format!("Invalid hex color: {hex:?}"));
+  }
+  let channel = |index: usize| u8::from_str_radix(&digits[index..index + 2], 16).unwrap_or(0);
+  let alpha = if digits.len() == 8 { channel(6) } else { 255 };
+  Ok(Color(channel(0), channel(2), channel(4), alpha))
+}
+
+/// Tints the native chrome behind the transparent macOS titlebar; elsewhere the webview
+/// background is used as the closest equivalent.
+pub fn apply_accent_color(window: &WebviewWindow, color: Color) -> Result<(), String> {
+  #[cfg(target_os = "macos")]
+  {
+    let target = window.clone();
+    window
+      .run_on_main_thread(move || {
+        use cocoa::appkit::{NSColor, NSWindow};
+        use cocoa::base::nil;
+
+        if let Some(ns_window) = crate::titlebar::ns_window_for(&target, "accent color") {
+          let Color(r, g, b, a) = color;
+          unsafe {
+            let ns_color = NSColor::colorWithSRGBRed_green_blue_alpha_(
+              nil,
+              f64::from(r) / 255.0,
+              f64::from(g) / 255.0,
+              f64::from(b) / 255.0,
+              f64::from(a) / 255.0,
+            );
+            ns_window.setBackgroundColor_(ns_color);
+          }
+        }
+      })
+      .map_err(|err| err.to_string())
+  }
+
+  #[cfg(not(target_os = "macos"))]
+  {
+    window
+      .set_background_color(Some(color))
+      .map_err(|err| err.to_string())
+  }
+}
+
+/// Re-applies the persisted accent color, if any, during setup.
+pub fn restore_accent_color(window: &WebviewWindow) {
+  let preferences = window.state::<Preferences>();
+  let Some(hex) = preferences.get(preferences::ACCENT_COLOR) else {
+    return;
+  };
+  let Some(hex) = hex.as_str() else {
+    return;
+  };
+  match parse_hex_color(hex).and_then(|color| apply_accent_color(window, color)) {
+    Ok(()) => {}
+    Err(err) => log::warn!("failed to restore accent color: {err}"),
+  }
+}
+
+#[tauri::command]
+pub fn set_accent_color(
+  window: WebviewWindow,
+  preferences: State<'_, Preferences>,
+  hex: String,
+) -> Result<(), String> {
+  let color = parse_hex_color(&hex)?;
+  apply_accent_color(&window, color)?;
+  preferences.set(preferences::ACCENT_COLOR, serde_json::Value::String(hex))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn parses_hex_colors_with_and_without_alpha() {
+    assert_eq!(parse_hex_color("#1a2B3c").unwrap(), Color(0x1a, 0x2b, 0x3c, 255));
+    assert_eq!(parse_hex_color(" 1a2b3c80 ").unwrap(), Color(0x1a, 0x2b, 0x3c, 0x80));
+  }
+
+  #[test]
+  fn rejects_malformed_hex_colors() {
+    for hex in ["", "#", "#fff", "#1a2b3c4", "#1a2b3g", "#1a2b3c4d5e"] {
+      assert!(parse_hex_color(hex).is_err(), "{hex:?} should be rejected");
+    }
+  }
+}
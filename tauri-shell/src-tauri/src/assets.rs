@@ -0,0 +1,69 @@
+//! `pluto://` protocol serving pages compiled into the shell, so UI that matters when the
+//! backend is down (the connection error page) never depends on it being up, and so the page
+//! shown after a webview crash loads in a fresh content process without any network.
+
+use std::borrow::Cow;
+
+use tauri::http::{header, Request, Response, StatusCode};
+use tauri::Url;
+
+pub const SCHEME: &str = "pluto";
+pub const CONNECTION_ERROR_PAGE: &str = "connection-error.html";
+pub const WEBVIEW_CRASHED_PAGE: &str = "webview-crashed.html";
+
+const ASSETS: &[(&str, &[u8])] = &[
+  (
+    CONNECTION_ERROR_PAGE,
+    include_bytes!("assets/connection-error.html"),
+  ),
+  (
+    WEBVIEW_CRASHED_PAGE,
+    include_bytes!("assets/webview-crashed.html"),
+  ),
+];
+
+/// The URL an embedded asset is served at. WebView2 only allows custom schemes through its
+/// `http://<scheme>.localhost` mapping, so Windows differs from the other platforms.
+pub fn url(path: &str) -> Url {
+  let base = if cfg!(windows) {
+    format!("http://{SCHEME}.localhost/")
+  } else {
+    format!("{SCHEME}://localhost/")
+  };
+  let base = Url::parse(&base).expect("asset base URL is valid");
+  base.join(path).unwrap_or(base)
+}
+
+/// Whether a webview is showing one of our embedded pages.
+pub fn is_asset_url(url: &Url) -> bool {
+  url.scheme() == SCHEME || url.host_str() == Some(&format!("{SCHEME}.localhost"))
+}
+
+/// Handler for `register_uri_scheme_protocol`.
+pub fn serve(request: &Request<Vec<u8>>) -> Response<Cow<'static, [u8]>> {
+  let path = request.uri().path().trim_start_matches('/');
+  let Some((name, bytes)) = ASSETS.iter().find(|(name, _)| *name == path) else {
+    return Response::builder()
+      .status(StatusCode::NOT_FOUND)
+      .body(Cow::Borrowed(&[][..]))
+      .unwrap_or_default();
+  };
+  Response::builder()
+    .header(header::CONTENT_TYPE, mime_type(name))
+    .body(Cow::Borrowed(*bytes))
+    .unwrap_or_default()
+}
+
+fn mime_type(name: &str) -> &'static str {
+  match name.rsplit_once('.').map(|(_, extension)| extension) {
+    Some("html") => "text/html; charset=utf-8",
+    Some("css") => "text/css; charset=utf-8",
+    Some("js") => "text/javascript; charset=utf-8",
+    Some("json") => "application/json",
+    Some("svg") => "image/svg+xml",
+    Some("png") => "image/png",
+    Some("ico") => "image/x-icon",
+    Some("woff2") => "font/woff2",
+    _ => "application/octet-stream",
+  }
+}
@@ -0,0 +1,38 @@
+use tauri::plugin::TauriPlugin;
+use tauri::{AppHandle, Runtime};
+use tauri_plugin_autostart::{MacosLauncher, ManagerExt};
+
+/// Passed by the login item so a session started at login can stay in the tray.
+const AUTOSTART_ARG: &str = "--autostart";
+
+pub fn init<R: Runtime>() -> TauriPlugin<R> {
+  tauri_plugin_autostart::init(MacosLauncher::LaunchAgent, Some(vec![AUTOSTART_ARG]))
+}
+
+pub fn is_autostart_launch() -> bool {
+  std::env::args().any(|arg| arg == AUTOSTART_ARG)
+}
+
+#[tauri::command]
+pub fn launched_at_login() -> bool {
+  is_autostart_launch()
+}
+
+#[tauri::command]
+pub fn get_autostart(app: AppHandle) -> bool {
+  app.autolaunch().is_enabled().unwrap_or_else(|err| {
+    log::warn!("failed to query autostart state: {err}");
+    false
+  })
+}
+
+#[tauri::command]
+pub fn set_autostart(app: AppHandle, enabled: bool) -> Result<(), String> {
+  let autolaunch = app.autolaunch();
+  let result = if enabled {
+    autolaunch.enable()
+  } else {
+    autolaunch.disable()
+  };
+  result.map_err(|err| format!("Failed to update autostart: {err}"))
+}
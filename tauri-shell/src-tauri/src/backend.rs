@@ -0,0 +1,705 @@
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, Stdio};
+use std::sync::atomic::{AtomicBool, AtomicU16, AtomicU32, Ordering};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+use anyhow::{anyhow, Context, Result};
+use log::{error, info, warn};
+use tauri::{App, AppHandle, Emitter, Manager};
+
+use crate::config::{self, EnvConfig};
+use crate::preferences::{self, Preferences};
+use crate::limits::{self, MemoryLimit};
+use crate::{
+  antivirus, crash, developer, feature_flags, integrity, locale, logs, process, readiness,
+  request_headers, secrets, setup_script, startup_trace, status, suspend, tasks, tls, volume,
+  window, workers,
+};
+
+const BACKEND_BINARY_DEBUG: &str = "../../dist/pluto-duck-backend/pluto-duck-backend";
+const BACKEND_RESOURCE_PATH: &str = "_up_/_up_/dist/pluto-duck-backend/pluto-duck-backend";
+/// Sidecar name from `bundle.externalBin`, for single-file builds of the backend.
+const SIDECAR_NAME: &str = "pluto-duck-backend";
+const BACKEND_PORT: u16 = 8123;
+/// Written by the backend once it is listening, in case it had to bind a different port.
+pub const PORT_FILE: &str = "server.port";
+const WRITE_PROBE_FILE: &str = ".write-test";
+const PORT_FILE_TIMEOUT: Duration = Duration::from_secs(60);
+const CWD_ENV: &str = "PLUTODUCK_SERVER_CWD";
+const LOG_TO_STDERR_ENV: &str = "PLUTODUCK_LOG_TO_STDERR";
+const INSPECT_ENV: &str = "PLUTODUCK_BACKEND_INSPECT";
+const DEFAULT_INSPECT_PORT: u16 = 5678;
+const SHUTDOWN_GRACE_ENV: &str = "PLUTODUCK_SHUTDOWN_GRACE_SECS";
+const DEFAULT_SHUTDOWN_GRACE: Duration = Duration::from_secs(5);
+const SHUTDOWN_POLL_INTERVAL: Duration = Duration::from_millis(100);
+const EXIT_WATCH_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Port the backend is actually listening on; starts as the requested port.
+static ACTIVE_PORT: AtomicU16 = AtomicU16::new(BACKEND_PORT);
+/// Port it was asked to bind, which is what a configured `frontendUrl` names.
+static REQUESTED_PORT: AtomicU16 = AtomicU16::new(BACKEND_PORT);
+/// Host configured in `EnvConfig`, unless it is a wildcard bind address; loopback otherwise.
+static BACKEND_HOST: OnceLock<String> = OnceLock::new();
+/// Pid of a backend being given its grace period by [`stop_child`]; 0 otherwise. The child
+/// has already left the state by then, so this is how `force_kill_server` still finds it.
+static STOPPING_PID: AtomicU32 = AtomicU32::new(0);
+/// Set while the backend is down because the window is hidden, so showing it restarts it.
+static STOPPED_WHILE_HIDDEN: AtomicBool = AtomicBool::new(false);
+
+struct BackendProcess(Arc<Mutex<Option<Child>>>);
+
+impl Drop for BackendProcess {
+  fn drop(&mut self) {
+    info!("BackendProcess dropping - killing backend");
+    if let Ok(mut guard) = self.0.lock() {
+      if let Some(mut child) = guard.take() {
+        info!("Stopping backend process...");
+        stop_child(&mut child);
+        info!("Backend process stopped");
+      }
+    }
+  }
+}
+
+/// Stops the backend when Tauri clears the app's resource table in `cleanup_before_exit`.
+/// The updater calls that right before handing over to the Windows installer, and `restart()`
+/// from the main thread does too; neither path emits `RunEvent::Exit`.
+struct ShutdownOnCleanup(AppHandle);
+
+impl tauri::Resource for ShutdownOnCleanup {}
+
+impl Drop for ShutdownOnCleanup {
+  fn drop(&mut self) {
+    shutdown(&self.0);
+  }
+}
+
+pub type BackendState = Arc<Mutex<Option<Child>>>;
+
+/// Whether a backend child is tracked and has not exited yet.
+pub fn is_running(state: &BackendState) -> bool {
+  match state.lock() {
+    Ok(mut guard) => match guard.as_mut() {
+      Some(child) => matches!(child.try_wait(), Ok(None)),
+      None => false,
+    },
+    Err(_) => false,
+  }
+}
+
+pub fn launch(app: &mut App) -> Result<()> {
+  setup_script::run(app.handle())?;
+  let child = spawn(app.handle())?;
+  let state: BackendState = Arc::new(Mutex::new(Some(child)));
+  let process_wrapper = BackendProcess(state.clone());
+
+  app.manage(state);
+  app.manage(process_wrapper);
+  app.resources_table().add(ShutdownOnCleanup(app.handle().clone()));
+  logs::forward_to_frontend(app.handle().clone(), &resolve_data_root(app.handle()));
+  workers::launch(app.handle());
+  info!("backend health will be checked by frontend polling");
+  Ok(())
+}
+
+fn spawn(app_handle: &AppHandle) -> Result<Child> {
+  let binary = backend_binary_path(app_handle)?;
+  if !is_sidecar(&binary) {
+    if let Some(backend_dir) = binary.parent() {
+      integrity::verify_backend_dir(backend_dir)?;
+    }
+  }
+  let data_root = resolve_data_root(app_handle);
+  startup_trace::open(&data_root);
+  startup_trace::record("data root", || data_root.display().to_string());
+
+  info!(
+    "launching backend binary {:?} with data root {:?}",
+    binary,
+    data_root
+  );
+
+  volume::verify_present(&data_root)?;
+  verify_writable(&data_root)?;
+  let log_dir = logs::log_dir(&data_root);
+  std::fs::create_dir_all(&log_dir).context("failed to create log directory")?;
+  crash::begin_session(&data_root, &log_dir);
+  let append = logs::append_logs(app_handle);
+  let stdout_log = logs::open_session_log(&log_dir.join(logs::STDOUT_LOG), append)
+    .context("failed to create stdout log")?;
+  let stderr_log = logs::open_session_log(&log_dir.join(logs::STDERR_LOG), append)
+    .context("failed to create stderr log")?;
+  // Running the packaged app from a terminal can stream backend output there instead.
+  let (stdout, stderr) = if config::env_flag(LOG_TO_STDERR_ENV) {
+    info!("{LOG_TO_STDERR_ENV} set; backend output goes to this terminal instead of log files");
+    (Stdio::inherit(), Stdio::inherit())
+  } else {
+    (Stdio::from(stdout_log), Stdio::from(stderr_log))
+  };
+
+  // A stale port file from the last session must not be mistaken for this one's.
+  let port_file = data_root.join(PORT_FILE);
+  let _ = std::fs::remove_file(&port_file);
+
+  let env_config = app_handle.state::<EnvConfig>();
+  let requested_port = env_config.port.unwrap_or(BACKEND_PORT);
+  ACTIVE_PORT.store(requested_port, Ordering::SeqCst);
+  REQUESTED_PORT.store(requested_port, Ordering::SeqCst);
+
+  let secrets_file = secrets::write_for_backend(&data_root, &env_config.backend_secrets)?;
+
+  let mut command = Command::new(&binary);
+  if let Some(cwd) = working_dir_override()? {
+    info!("backend working directory overridden to {}", cwd.display());
+    command.current_dir(cwd);
+  } else if let Some(parent) = binary.parent() {
+    command.current_dir(parent);
+  }
+  locale::apply_to(&mut command);
+  command
+    .envs(&env_config.backend_env)
+    .env("PLUTODUCK_DATA_DIR__ROOT", &data_root)
+    .args([
+      "--port",
+      &requested_port.to_string(),
+      "--data-root",
+      data_root.to_string_lossy().as_ref(),
+    ])
+    .stdout(stdout)
+    .stderr(stderr);
+  if let Some(path) = &secrets_file {
+    command.env(secrets::SECRETS_FILE_ENV, path);
+  }
+  if let Some(flags) = feature_flags::backend_env() {
+    command.env(feature_flags::FEATURE_FLAGS_ENV, flags);
+  }
+  if tls::enabled() {
+    let certs = tls::ensure_cert(&data_root)?;
+    command.arg("--ssl-certfile").arg(&certs.cert);
+    command.arg("--ssl-keyfile").arg(&certs.key);
+  }
+  if let Some(host) = &env_config.host {
+    command.args(["--host", host]);
+    if !matches!(host.as_str(), "0.0.0.0" | "::" | "[::]") {
+      let _ = BACKEND_HOST.set(host.clone());
+    }
+  }
+  if let Some(address) = inspect_address(app_handle) {
+    command.args(["--inspect", &address]);
+    info!("backend debugger enabled; attach a debugpy client (e.g. VS Code) to {address}");
+  }
+
+  // Not the Command's Debug output: that includes the environment, which may hold secrets.
+  startup_trace::record("spawn", || {
+    let args: Vec<_> = command.get_args().collect();
+    format!("{:?} {args:?} in {:?}", command.get_program(), command.get_current_dir())
+  });
+  let child = command
+    .spawn()
+    .inspect_err(|err| startup_trace::record("spawn failed", || err.to_string()))
+    .map_err(|err| antivirus::spawn_error(&binary, &log_dir, err))?;
+  startup_trace::record("spawned", || format!("pid {} on {}", child.id(), origin()));
+  info!(
+    "backend process spawned on {} with data root {:?}",
+    origin(),
+    data_root
+  );
+
+  suspend::backend_replaced(app_handle);
+  let limit = limits::apply_configured(&child);
+  watch_exit(app_handle.clone(), child.id(), limit);
+  volume::watch(app_handle.clone(), child.id(), data_root.clone());
+  watch_port_file(app_handle.clone(), port_file);
+  status::watch(app_handle.clone());
+  Ok(child)
+}
+
+/// Spawns a replacement for a backend that was running, emits `backend-restarted`, and has
+/// the windows reconnect once the new process reports ready.
+fn respawn(app: &AppHandle) -> Result<Child> {
+  status::reconnect_when_ready(true);
+  let child = spawn(app).inspect_err(|_| status::reconnect_when_ready(false))?;
+  let _ = app.emit("backend-restarted", ());
+  Ok(child)
+}
+
+/// Notices the backend exiting on its own: records a crash report if the user opted in, and
+/// respawns it if the OS took it down for exceeding its memory limit. Stops of our own take
+/// the child out of the state first, which ends the watch.
+fn watch_exit(app: AppHandle, pid: u32, limit: Option<MemoryLimit>) {
+  tasks::spawn("backend-exit-watch", move || loop {
+    std::thread::sleep(EXIT_WATCH_INTERVAL);
+    let Some(state) = app.try_state::<BackendState>() else {
+      continue;
+    };
+    let Ok(mut guard) = state.lock() else {
+      return;
+    };
+    let status = match guard.as_mut() {
+      Some(child) if child.id() == pid => match child.try_wait() {
+        Ok(None) => continue,
+        Ok(Some(status)) => status,
+        Err(_) => return,
+      },
+      _ => return,
+    };
+    warn!("backend exited unexpectedly with {status}");
+    // Before any respawn, which truncates the logs the report quotes.
+    crash::record_report(&app, &status);
+    let Some(limit) = limit.filter(MemoryLimit::was_hit) else {
+      return;
+    };
+    error!(
+      "backend exceeded its {} MB memory limit and exited with {status}; restarting it",
+      limit.limit_mb()
+    );
+    match respawn(&app) {
+      Ok(child) => *guard = Some(child),
+      Err(err) => error!("failed to restart backend: {err:?}"),
+    }
+    return;
+  });
+}
+
+/// Frees the backend's memory while the window is hidden, if the user turned off
+/// `keep_server_running_when_hidden`. [`start_if_stopped_while_hidden`] brings it back.
+/// All windows share the one backend, so it keeps running while any of them is visible.
+pub fn stop_while_hidden(app: &AppHandle) {
+  suspend::schedule(app);
+  let keep_running = app
+    .state::<Preferences>()
+    .get_bool(preferences::KEEP_SERVER_RUNNING_WHEN_HIDDEN, true);
+  let Some(state) = app.try_state::<BackendState>() else {
+    return;
+  };
+  if keep_running || window::any_visible(app) || STOPPED_WHILE_HIDDEN.swap(true, Ordering::SeqCst) {
+    return;
+  }
+  let state = state.inner().clone();
+  let app = app.clone();
+  // The grace period can take seconds; holding the lock meanwhile makes a quick re-show wait
+  // for the old process to exit before a new one binds the port.
+  tasks::spawn("stop-backend-while-hidden", move || {
+    if let Ok(mut guard) = state.lock() {
+      if let Some(mut child) = guard.take() {
+        info!("all windows hidden; stopping backend to free memory");
+        stop_child(&mut child);
+        crash::end_session(&resolve_data_root(&app));
+      }
+    }
+  });
+}
+
+pub fn start_if_stopped_while_hidden(app: &AppHandle) {
+  suspend::resume(app);
+  let Some(state) = app.try_state::<BackendState>() else {
+    return;
+  };
+  if !STOPPED_WHILE_HIDDEN.swap(false, Ordering::SeqCst) {
+    return;
+  }
+  let state = state.inner().clone();
+  let app = app.clone();
+  tasks::spawn("restart-backend-on-show", move || {
+    let Ok(mut guard) = state.lock() else {
+      return;
+    };
+    if guard.is_some() {
+      return;
+    }
+    info!("window shown; restarting backend");
+    match respawn(&app) {
+      Ok(child) => *guard = Some(child),
+      Err(err) => error!("failed to restart backend: {err:?}"),
+    }
+  });
+}
+
+/// Runs `f` with the backend and its workers stopped so nothing under the data root is in use
+/// (Windows refuses to replace open files), then starts them again.
+pub fn with_backend_stopped<T>(app: &AppHandle, f: impl FnOnce() -> Result<T>) -> Result<T> {
+  let state = app
+    .try_state::<BackendState>()
+    .context("backend is not initialized")?;
+  let mut guard = state
+    .lock()
+    .map_err(|_| anyhow!("backend state lock is poisoned"))?;
+  workers::stop_all(app);
+  let was_running = match guard.take() {
+    Some(mut child) => {
+      info!("stopping backend");
+      stop_child(&mut child);
+      crash::end_session(&resolve_data_root(app));
+      true
+    }
+    None => false,
+  };
+
+  let result = f();
+  if was_running {
+    match respawn(app) {
+      Ok(child) => *guard = Some(child),
+      Err(err) if result.is_ok() => return Err(err.context("failed to restart backend")),
+      Err(err) => error!("failed to restart backend: {err:?}"),
+    }
+  }
+  workers::launch(app);
+  result
+}
+
+/// Restarts a running backend together with its workers; a stopped one stays stopped.
+pub fn restart(app: &AppHandle) -> Result<()> {
+  info!("restarting backend");
+  with_backend_stopped(app, || Ok(()))
+}
+
+/// Working directory from `PLUTODUCK_SERVER_CWD`, for pointing the backend's relative paths
+/// somewhere other than its install directory. It must be an existing, readable directory.
+fn working_dir_override() -> Result<Option<PathBuf>> {
+  let Some(value) = std::env::var_os(CWD_ENV).filter(|value| !value.is_empty()) else {
+    return Ok(None);
+  };
+  let dir = PathBuf::from(value);
+  if !dir.is_dir() {
+    anyhow::bail!("{CWD_ENV} is not a directory: {}", dir.display());
+  }
+  std::fs::read_dir(&dir).with_context(|| format!("{CWD_ENV} is not accessible: {}", dir.display()))?;
+  Ok(Some(dir))
+}
+
+/// Debugger address requested via `PLUTODUCK_BACKEND_INSPECT`, either a port or a plain flag
+/// for the default one. Only honoured in debug builds or with developer mode on.
+fn inspect_address(app: &AppHandle) -> Option<String> {
+  let value = std::env::var(INSPECT_ENV).ok()?;
+  let port = if config::env_flag(INSPECT_ENV) {
+    DEFAULT_INSPECT_PORT
+  } else {
+    match value.trim().parse::<u16>() {
+      Ok(port) if port != 0 => port,
+      _ => {
+        warn!("ignoring {INSPECT_ENV}={value:?}: expected a port number or 1/true");
+        return None;
+      }
+    }
+  };
+  if !developer::is_enabled(app) {
+    warn!("ignoring {INSPECT_ENV}: developer mode is off");
+    return None;
+  }
+  Some(format!("127.0.0.1:{port}"))
+}
+
+/// Picks up the port the backend reports in `server.port` so shell-side requests follow it
+/// if it rebinds; the frontend gets a `backend-port-changed` event.
+fn watch_port_file(app: AppHandle, port_file: PathBuf) {
+  tasks::spawn("port-file-watch", move || {
+    let started = Instant::now();
+    let mut backoff = readiness::PollBackoff::default();
+    while started.elapsed() < PORT_FILE_TIMEOUT && !readiness::shutdown_requested() {
+      if let Some(port) = read_port_file(&port_file) {
+        startup_trace::record("port file", || format!("backend reported port {port}"));
+        let previous = ACTIVE_PORT.swap(port, Ordering::SeqCst);
+        if previous != port {
+          info!("backend reported port {port} (expected {previous})");
+          request_headers::publish_backend_origin(&app);
+          let _ = app.emit("backend-port-changed", port);
+        }
+        return;
+      }
+      backoff.sleep();
+    }
+  });
+}
+
+fn read_port_file(path: &Path) -> Option<u16> {
+  std::fs::read_to_string(path).ok()?.trim().parse().ok()
+}
+
+pub fn port() -> u16 {
+  ACTIVE_PORT.load(Ordering::SeqCst)
+}
+
+#[tauri::command]
+pub fn backend_port() -> u16 {
+  port()
+}
+
+/// Stops the backend and its workers on app exit and closes out the session marker.
+pub fn shutdown(app: &AppHandle) {
+  workers::stop_all(app);
+  let Some(state) = app.try_state::<BackendState>() else {
+    return;
+  };
+  let mut crashed = false;
+  if let Ok(mut guard) = state.lock() {
+    if let Some(mut child) = guard.take() {
+      match child.try_wait() {
+        Ok(Some(status)) if !status.success() => {
+          error!("backend had already exited with {status}; keeping its logs for the next launch");
+          crashed = true;
+        }
+        Ok(Some(_)) => {}
+        _ => {
+          info!("Stopping backend process on exit...");
+          stop_child(&mut child);
+          info!("Backend process stopped on exit");
+        }
+      }
+    }
+  }
+  if !crashed {
+    crash::end_session(&resolve_data_root(app));
+  }
+}
+
+fn shutdown_grace() -> Duration {
+  std::env::var(SHUTDOWN_GRACE_ENV)
+    .ok()
+    .and_then(|value| value.trim().parse::<u64>().ok())
+    .map(Duration::from_secs)
+    .unwrap_or(DEFAULT_SHUTDOWN_GRACE)
+}
+
+/// Asks the backend to exit so an in-flight DuckDB write can finish, and only force-kills it
+/// once `PLUTODUCK_SHUTDOWN_GRACE_SECS` (default 5, 0 to skip) runs out.
+pub fn stop_child(child: &mut Child) {
+  let grace = shutdown_grace();
+  if !grace.is_zero() {
+    match process::terminate(child.id()) {
+      Ok(()) => {
+        STOPPING_PID.store(child.id(), Ordering::SeqCst);
+        let started = Instant::now();
+        while started.elapsed() < grace {
+          match child.try_wait() {
+            Ok(Some(status)) => {
+              info!("backend exited with {status} after {:?}", started.elapsed());
+              STOPPING_PID.store(0, Ordering::SeqCst);
+              return;
+            }
+            Ok(None) => std::thread::sleep(SHUTDOWN_POLL_INTERVAL),
+            Err(_) => break,
+          }
+        }
+        warn!("backend did not exit within {grace:?}; killing it");
+      }
+      Err(err) => warn!("graceful backend shutdown unavailable: {err}"),
+    }
+  }
+  let _ = child.kill();
+  let _ = child.wait();
+  STOPPING_PID.store(0, Ordering::SeqCst);
+}
+
+/// Developer escape hatch for a stop that hangs: kills the backend outright, skipping the
+/// grace period, whether it is running or in the middle of a graceful stop. A running backend
+/// is treated like one stopped while hidden, so the next window show starts a new one; a
+/// stop in progress carries on as it would have, e.g. a restart still respawns. Emits
+/// `backend-killed`. The session is not marked as ended cleanly, so the next launch keeps its
+/// logs.
+#[tauri::command]
+pub fn force_kill_server(
+  app: AppHandle,
+  state: tauri::State<'_, BackendState>,
+) -> Result<(), String> {
+  developer::ensure_enabled(&app)?;
+  let stopping = STOPPING_PID.load(Ordering::SeqCst);
+  let pid = if stopping != 0 {
+    // The stopping thread holds the state lock; killing by pid lets its wait return.
+    process::kill(stopping)?;
+    stopping
+  } else {
+    let mut guard = state
+      .try_lock()
+      .map_err(|_| "The backend is busy starting or stopping; try again".to_string())?;
+    let mut child = guard.take().ok_or("Backend is not running")?;
+    let pid = child.id();
+    child.kill().map_err(|err| format!("Failed to kill backend process {pid}: {err}"))?;
+    let _ = child.wait();
+    STOPPED_WHILE_HIDDEN.store(true, Ordering::SeqCst);
+    pid
+  };
+  warn!("backend process {pid} force-killed");
+  let _ = app.emit("backend-killed", pid);
+  Ok(())
+}
+
+pub fn running_pid(state: &BackendState) -> Result<u32, String> {
+  let mut guard = state.lock().map_err(|err| err.to_string())?;
+  match guard.as_mut() {
+    Some(child) => match child.try_wait() {
+      Ok(None) => Ok(child.id()),
+      _ => Err("Backend is not running".to_string()),
+    },
+    None => Err("Backend is not running".to_string()),
+  }
+}
+
+/// Freezes the backend in place for debugging timing-dependent issues.
+#[tauri::command]
+pub fn pause_server(app: AppHandle, state: tauri::State<'_, BackendState>) -> Result<(), String> {
+  developer::ensure_enabled(&app)?;
+  let pid = running_pid(&state)?;
+  process::suspend(pid)?;
+  info!("backend process {pid} paused");
+  Ok(())
+}
+
+#[tauri::command]
+pub fn resume_server(app: AppHandle, state: tauri::State<'_, BackendState>) -> Result<(), String> {
+  developer::ensure_enabled(&app)?;
+  let pid = running_pid(&state)?;
+  process::resume(pid)?;
+  info!("backend process {pid} resumed");
+  Ok(())
+}
+
+pub fn origin() -> String {
+  let host = BACKEND_HOST.get().map(String::as_str).unwrap_or("127.0.0.1");
+  format!("{}://{host}:{}", tls::scheme(), port())
+}
+
+/// Points `url` at the port the backend actually bound when it names the requested one on a
+/// loopback host, i.e. when the backend serves the frontend itself. The scheme follows
+/// `PLUTODUCK_FRONTEND_TLS` too.
+pub fn follow_active_port(url: &mut tauri::Url) {
+  let loopback = matches!(url.host_str(), Some("127.0.0.1" | "localhost" | "[::1]"));
+  if loopback && url.port() == Some(REQUESTED_PORT.load(Ordering::SeqCst)) {
+    let _ = url.set_port(Some(port()));
+    let _ = url.set_scheme(tls::scheme());
+  }
+}
+
+pub fn health_url() -> String {
+  format!("{}/health", origin())
+}
+
+/// Directory the backend runs from, resolved exactly as `launch` does (sidecar first in
+/// release builds, then the bundled resources), for diagnosing packaging problems.
+#[tauri::command]
+pub fn server_root_path(app: AppHandle) -> Result<String, String> {
+  let binary = backend_binary_path(&app).map_err(|err| err.to_string())?;
+  let root = binary.parent().unwrap_or(&binary);
+  Ok(root.to_string_lossy().into_owned())
+}
+
+/// Where Tauri puts a `bundle.externalBin` sidecar: next to the app executable, without the
+/// target-triple suffix it has at build time. Whether one is actually there is up to the caller.
+fn sidecar_path() -> Result<PathBuf, String> {
+  let exe =
+    std::env::current_exe().map_err(|err| format!("app executable path unavailable: {err}"))?;
+  let dir = exe.parent().ok_or("app executable has no parent directory")?;
+  Ok(dir.join(format!("{SIDECAR_NAME}{}", std::env::consts::EXE_SUFFIX)))
+}
+
+/// A sidecar is a single-file build, so it has no onedir layout for the integrity check.
+pub fn is_sidecar(binary: &Path) -> bool {
+  !cfg!(debug_assertions) && sidecar_path().is_ok_and(|sidecar| sidecar == binary)
+}
+
+/// Release builds prefer a bundled sidecar and fall back to the onedir backend in resources.
+/// When neither is there, every candidate and why it was rejected is logged and returned, since
+/// packaging layouts differ per platform and a bare "not found" hides which one was wrong.
+pub fn backend_binary_path(app: &AppHandle) -> Result<PathBuf> {
+  let mut tried = Vec::new();
+  for (source, candidate) in binary_candidates(app) {
+    let reason = match candidate {
+      Ok(path) if path.is_file() => {
+        startup_trace::record("backend binary", || format!("{source}: {}", path.display()));
+        return Ok(path);
+      }
+      Ok(path) if path.exists() => format!("{} is not a file", path.display()),
+      Ok(path) => format!("{} does not exist", path.display()),
+      Err(reason) => reason,
+    };
+    error!("backend binary candidate ({source}) rejected: {reason}");
+    startup_trace::record("backend binary rejected", || format!("{source}: {reason}"));
+    tried.push(format!("{source}: {reason}"));
+  }
+  anyhow::bail!("backend binary not found; tried {}", tried.join("; "))
+}
+
+/// Candidate backend locations in order of preference, labelled for diagnostics; `Err` when
+/// the location itself couldn't be determined.
+fn binary_candidates(app: &AppHandle) -> Vec<(&'static str, Result<PathBuf, String>)> {
+  if cfg!(debug_assertions) {
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join(BACKEND_BINARY_DEBUG);
+    return vec![("development build", Ok(path))];
+  }
+  let resource = app
+    .path()
+    .resource_dir()
+    .map(|dir| dir.join(BACKEND_RESOURCE_PATH))
+    .map_err(|err| format!("resource directory unavailable: {err}"));
+  vec![("sidecar", sidecar_path()), ("bundled resources", resource)]
+}
+
+pub fn resolve_data_root(app: &AppHandle) -> PathBuf {
+  let base = if cfg!(debug_assertions) {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../../.dev-data")
+  } else {
+    app
+      .path()
+      .app_data_dir()
+      .unwrap_or_else(|_| std::env::temp_dir().join("pluto_duck"))
+  };
+  let root = base.join("backend");
+  if let Err(err) = std::fs::create_dir_all(logs::log_dir(&root)) {
+    error!("failed to create backend data directories: {err}");
+  }
+  restrict_permissions(&root);
+  restrict_permissions(&logs::log_dir(&root));
+  root
+}
+
+/// Owner-only access (0700) regardless of umask, since the data root holds the user's
+/// databases and credentials. No-op on Windows, where the profile directory is already private.
+fn restrict_permissions(dir: &Path) {
+  #[cfg(unix)]
+  {
+    use std::os::unix::fs::PermissionsExt;
+
+    let Ok(metadata) = std::fs::metadata(dir) else {
+      return;
+    };
+    if metadata.permissions().mode() & 0o777 == 0o700 {
+      return;
+    }
+    if let Err(err) = std::fs::set_permissions(dir, std::fs::Permissions::from_mode(0o700)) {
+      warn!("failed to restrict permissions on {}: {err}", dir.display());
+    }
+  }
+  #[cfg(not(unix))]
+  let _ = dir;
+}
+
+/// Launch failure because the backend could not write its data root; setup shows it to the
+/// user since the backend would otherwise fail in obscure ways on first run.
+#[derive(Debug)]
+pub struct DataRootNotWritable {
+  pub path: PathBuf,
+  pub reason: String,
+}
+
+impl std::fmt::Display for DataRootNotWritable {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    write!(
+      f,
+      "Pluto Duck can't write to its data folder {} ({}). Check that your user owns it and can write to it.",
+      self.path.display(),
+      self.reason
+    )
+  }
+}
+
+impl std::error::Error for DataRootNotWritable {}
+
+/// Writes and removes a probe file, so a data root with wrong permissions fails up front.
+fn verify_writable(data_root: &Path) -> std::result::Result<(), DataRootNotWritable> {
+  let probe = data_root.join(WRITE_PROBE_FILE);
+  let result = std::fs::write(&probe, b"ok").and_then(|()| std::fs::remove_file(&probe));
+  result.map_err(|err| DataRootNotWritable {
+    path: data_root.to_path_buf(),
+    reason: err.to_string(),
+  })
+}
@@ -0,0 +1,236 @@
+use std::fs::File;
+use std::io::{BufReader, BufWriter};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, State};
+
+use crate::{api, backend, crash, logs, secrets, startup_trace, tls};
+
+const CHECKPOINT_PATH: &str = "/api/v1/system/checkpoint";
+// Large databases can take a while to flush the WAL.
+const CHECKPOINT_TIMEOUT: Duration = Duration::from_secs(120);
+/// Written into every backup; its presence is what makes a zip restorable.
+const MANIFEST_NAME: &str = "pluto-duck-backup.json";
+const BACKUP_FORMAT: u32 = 1;
+/// Session state and logs belong to the running install, not to the data being backed up.
+const EXCLUDED_ENTRIES: [&str; 9] = [
+  logs::LOG_DIR,
+  crash::CRASHES_DIR,
+  crash::CRASH_REPORTS_DIR,
+  crash::SESSION_MARKER,
+  backend::PORT_FILE,
+  secrets::SECRETS_FILE,
+  secrets::WORKER_SECRETS_DIR,
+  startup_trace::TRACE_FILE,
+  tls::TLS_DIR,
+];
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BackupManifest {
+  format: u32,
+  app_version: String,
+  created_at: String,
+}
+
+/// Asks the backend to CHECKPOINT DuckDB so the database file is consistent on disk
+/// and safe to copy while the app keeps running.
+#[tauri::command]
+pub async fn checkpoint_database(state: State<'_, backend::BackendState>) -> Result<(), String> {
+  if !backend::is_running(&state) {
+    return Err("Backend is not running".to_string());
+  }
+  api::post(CHECKPOINT_PATH, CHECKPOINT_TIMEOUT).await?;
+  log::info!("backend database checkpoint completed");
+  Ok(())
+}
+
+/// Zips the data root into `dest` (a directory) as a timestamped backup and returns its path.
+/// The backend is checkpointed if it is running, then stopped while the files are read. The
+/// WAL is copied along with the database, so a failed checkpoint doesn't stop the backup.
+#[tauri::command]
+pub async fn backup_data(
+  app: AppHandle,
+  state: State<'_, backend::BackendState>,
+  dest: PathBuf,
+) -> Result<PathBuf, String> {
+  if !dest.is_dir() {
+    return Err(format!("Backup destination {} is not a directory", dest.display()));
+  }
+  if backend::is_running(&state) {
+    if let Err(err) = api::post(CHECKPOINT_PATH, CHECKPOINT_TIMEOUT).await {
+      log::warn!("checkpoint before backup failed; backing up the WAL as is: {err}");
+    }
+  }
+
+  let stamp = chrono::Local::now().format("%Y%m%d-%H%M%S");
+  let archive = dest.join(format!("pluto-duck-backup-{stamp}.zip"));
+  let result = {
+    let archive = archive.clone();
+    tauri::async_runtime::spawn_blocking(move || {
+      let data_root = backend::resolve_data_root(&app);
+      if archive.starts_with(&data_root) {
+        bail!("the backup cannot be written inside the data directory");
+      }
+      backend::with_backend_stopped(&app, || write_backup(&data_root, &archive, &app))
+    })
+    .await
+    .map_err(|err| err.to_string())?
+  };
+  match result {
+    Ok(()) => {
+      log::info!("data directory backed up to {}", archive.display());
+      Ok(archive)
+    }
+    Err(err) => {
+      let _ = std::fs::remove_file(&archive);
+      Err(format!("Backup failed: {err:#}"))
+    }
+  }
+}
+
+/// Replaces the data root with the contents of a backup made by [`backup_data`], with the
+/// backend stopped meanwhile. The current data is restored if the swap fails partway.
+#[tauri::command]
+pub async fn restore_data(app: AppHandle, src: PathBuf) -> Result<(), String> {
+  tauri::async_runtime::spawn_blocking(move || {
+    let mut archive = open_backup(&src)?;
+    let data_root = backend::resolve_data_root(&app);
+    backend::with_backend_stopped(&app, || restore_backup(&mut archive, &data_root))?;
+    log::info!("data directory restored from {}", src.display());
+    Ok(())
+  })
+  .await
+  .map_err(|err| err.to_string())?
+  .map_err(|err: anyhow::Error| format!("Restore failed: {err:#}"))
+}
+
+fn is_excluded(name: &std::ffi::OsStr) -> bool {
+  EXCLUDED_ENTRIES.iter().any(|excluded| name == *excluded)
+}
+
+fn write_backup(data_root: &Path, archive: &Path, app: &AppHandle) -> Result<()> {
+  let file = File::create(archive).context("failed to create backup file")?;
+  let mut writer = zip::ZipWriter::new(BufWriter::new(file));
+  let options = zip::write::SimpleFileOptions::default()
+    .compression_method(zip::CompressionMethod::Deflated);
+
+  let manifest = BackupManifest {
+    format: BACKUP_FORMAT,
+    app_version: app.package_info().version.to_string(),
+    created_at: chrono::Utc::now().to_rfc3339(),
+  };
+  writer.start_file(MANIFEST_NAME, options)?;
+  serde_json::to_writer_pretty(&mut writer, &manifest)?;
+
+  let mut pending = vec![data_root.to_path_buf()];
+  while let Some(dir) = pending.pop() {
+    let entries =
+      std::fs::read_dir(&dir).with_context(|| format!("failed to read {}", dir.display()))?;
+    for entry in entries {
+      let entry = entry?;
+      let path = entry.path();
+      if dir == data_root && is_excluded(&entry.file_name()) {
+        continue;
+      }
+      let relative = path.strip_prefix(data_root)?;
+      let name = relative
+        .components()
+        .map(|component| component.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/");
+      let file_type = entry.file_type()?;
+      if file_type.is_dir() {
+        writer.add_directory(name, options)?;
+        pending.push(path);
+      } else if file_type.is_file() {
+        let large = entry.metadata()?.len() >= u64::from(u32::MAX);
+        writer.start_file(name, options.large_file(large))?;
+        let mut source =
+          File::open(&path).with_context(|| format!("failed to read {}", path.display()))?;
+        std::io::copy(&mut source, &mut writer)?;
+      }
+    }
+  }
+  writer.finish()?;
+  Ok(())
+}
+
+/// Opens `src` and checks it is a backup this version can restore: a readable manifest and
+/// no entries that would escape the data directory.
+fn open_backup(src: &Path) -> Result<zip::ZipArchive<BufReader<File>>> {
+  let file = File::open(src).with_context(|| format!("failed to open {}", src.display()))?;
+  let mut archive = zip::ZipArchive::new(BufReader::new(file)).context("not a zip archive")?;
+
+  let manifest: BackupManifest = match archive.by_name(MANIFEST_NAME) {
+    Ok(entry) => serde_json::from_reader(entry).context("backup manifest is unreadable")?,
+    Err(_) => bail!("{} is not a Pluto Duck backup (missing {MANIFEST_NAME})", src.display()),
+  };
+  if manifest.format != BACKUP_FORMAT {
+    bail!("unsupported backup format {}", manifest.format);
+  }
+  for index in 0..archive.len() {
+    let entry = archive.by_index(index)?;
+    if entry.enclosed_name().is_none() {
+      bail!("backup contains an unsafe path: {}", entry.name());
+    }
+  }
+  log::info!(
+    "restoring backup created {} by version {}",
+    manifest.created_at,
+    manifest.app_version
+  );
+  Ok(archive)
+}
+
+/// Extracts next to the data root, then swaps entries over one by one so the log directory
+/// (held open by log forwarding) can stay in place.
+fn restore_backup(archive: &mut zip::ZipArchive<BufReader<File>>, data_root: &Path) -> Result<()> {
+  let staging = data_root.with_extension("restore");
+  let previous = data_root.with_extension("previous");
+  for dir in [&staging, &previous] {
+    if dir.exists() {
+      std::fs::remove_dir_all(dir).with_context(|| format!("failed to clear {}", dir.display()))?;
+    }
+    std::fs::create_dir_all(dir)?;
+  }
+  archive.extract(&staging).context("failed to extract backup")?;
+
+  std::fs::remove_file(staging.join(MANIFEST_NAME))?;
+
+  if let Err(err) = move_entries(data_root, &previous) {
+    let _ = move_entries(&previous, data_root);
+    return Err(err);
+  }
+  if let Err(err) = move_entries(&staging, data_root) {
+    log::error!("restore failed partway; putting the previous data back: {err:#}");
+    if let Ok(partial) = move_entries(data_root, &staging) {
+      log::info!("moved {} restored entries back out", partial.len());
+    }
+    move_entries(&previous, data_root).context("failed to put the previous data back")?;
+    return Err(err);
+  }
+
+  let _ = std::fs::remove_dir_all(&staging);
+  let _ = std::fs::remove_dir_all(&previous);
+  Ok(())
+}
+
+/// Moves every top-level entry of `from` into `to`, skipping excluded ones, and returns
+/// the names moved.
+fn move_entries(from: &Path, to: &Path) -> Result<Vec<std::ffi::OsString>> {
+  let mut moved = Vec::new();
+  for entry in std::fs::read_dir(from)? {
+    let name = entry?.file_name();
+    if is_excluded(&name) {
+      continue;
+    }
+    std::fs::rename(from.join(&name), to.join(&name))
+      .with_context(|| format!("failed to move {}", from.join(&name).display()))?;
+    moved.push(name);
+  }
+  Ok(moved)
+}
@@ -0,0 +1,125 @@
+//! White-label branding from a `branding.json`, so resellers can change the window title, icon
+//! and accent without forking the shell. The data root's copy wins over the one bundled in
+//! resources; without either the window keeps the stock Pluto Duck look.
+
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager, WebviewWindow};
+
+use crate::appearance;
+use crate::backend;
+use crate::preferences::{self, Preferences};
+use crate::window;
+
+const BRANDING_FILE: &str = "branding.json";
+const DEFAULT_TITLE: &str = "Pluto Duck";
+
+#[derive(Clone, Default, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Branding {
+  /// Replaces "Pluto Duck" as the window title.
+  pub title: Option<String>,
+  /// Put in front of the title, e.g. the reseller's name.
+  pub title_prefix: Option<String>,
+  /// PNG or ICO; relative paths are resolved against the directory of `branding.json`.
+  pub icon: Option<PathBuf>,
+  /// `#RRGGBB`; the user's own accent color still takes precedence.
+  pub accent_color: Option<String>,
+}
+
+impl Branding {
+  pub fn window_title(&self) -> String {
+    let title = self
+      .title
+      .as_deref()
+      .map(str::trim)
+      .filter(|title| !title.is_empty())
+      .unwrap_or(DEFAULT_TITLE);
+    match self.title_prefix.as_deref().map(str::trim) {
+      Some(prefix) if !prefix.is_empty() => format!("{prefix} {title}"),
+      _ => title.to_string(),
+    }
+  }
+}
+
+fn candidates(app: &AppHandle) -> Vec<PathBuf> {
+  let mut paths = vec![backend::resolve_data_root(app).join(BRANDING_FILE)];
+  if let Ok(dir) = app.path().resource_dir() {
+    paths.push(dir.join(BRANDING_FILE));
+  }
+  paths
+}
+
+/// The first `branding.json` found, with a relative icon path made absolute. A malformed file
+/// is reported and skipped rather than half-applied.
+pub fn load(app: &AppHandle) -> Branding {
+  for path in candidates(app) {
+    let Ok(contents) = std::fs::read_to_string(&path) else {
+      continue;
+    };
+    match serde_json::from_str::<Branding>(&contents) {
+      Ok(mut branding) => {
+        log::info!("using branding from {}", path.display());
+        let base = path.parent().unwrap_or(Path::new(""));
+        branding.icon = branding.icon.map(|icon| base.join(icon));
+        return branding;
+      }
+      Err(err) => log::warn!("ignoring malformed branding file {}: {err}", path.display()),
+    }
+  }
+  Branding::default()
+}
+
+/// Applies title, icon and accent to `window`. Each part is applied on its own so a bad icon
+/// doesn't cost the title; the errors are returned together.
+pub fn apply(window: &WebviewWindow, branding: &Branding) -> Result<(), String> {
+  let mut errors = Vec::new();
+  if let Err(err) = window.set_title(&branding.window_title()) {
+    errors.push(format!("title: {err}"));
+  }
+  if let Some(icon) = &branding.icon {
+    if let Err(err) = window::set_window_icon(window.clone(), icon.clone()) {
+      errors.push(format!("icon: {err}"));
+    }
+  }
+  let user_accent = window
+    .state::<Preferences>()
+    .get(preferences::ACCENT_COLOR)
+    .is_some();
+  if let Some(hex) = branding.accent_color.as_ref().filter(|_| !user_accent) {
+    let applied = appearance::parse_hex_color(hex)
+      .and_then(|color| appearance::apply_accent_color(window, color));
+    if let Err(err) = applied {
+      errors.push(format!("accent color: {err}"));
+    }
+  }
+  if errors.is_empty() {
+    Ok(())
+  } else {
+    Err(format!("Failed to apply branding ({})", errors.join("; ")))
+  }
+}
+
+/// Called while creating the main window; failures are logged, never fatal.
+pub fn restore(window: &WebviewWindow) {
+  if let Err(err) = apply(window, &load(window.app_handle())) {
+    log::warn!("{err}");
+  }
+}
+
+/// Re-reads `branding.json` and applies it to every window, e.g. after a reseller's tool
+/// replaced the file. Returns the branding in effect.
+#[tauri::command]
+pub fn reapply_branding(app: AppHandle) -> Result<Branding, String> {
+  let branding = load(&app);
+  let errors: Vec<String> = app
+    .webview_windows()
+    .values()
+    .filter_map(|window| apply(window, &branding).err())
+    .collect();
+  match errors.first() {
+    None => Ok(branding),
+    Some(err) => Err(err.clone()),
+  }
+}
@@ -0,0 +1,113 @@
+use std::collections::HashMap;
+use std::path::Path;
+
+use serde::Deserialize;
+use serde_json::{Map, Value};
+use tauri::{AppHandle, Manager, Url};
+
+use crate::setup_script::SetupConfig;
+use crate::workers::WorkerConfig;
+
+const ENV_NAME_VAR: &str = "PLUTODUCK_ENV";
+const DEFAULT_ENV_NAME: &str = "prod";
+const DEV_URL_VAR: &str = "PLUTODUCK_DEV_URL";
+
+/// Environment-driven switches for the shell. Values like `1`, `true` or `yes` enable a flag.
+pub fn env_flag(name: &str) -> bool {
+  std::env::var(name)
+    .map(|value| matches!(value.trim().to_ascii_lowercase().as_str(), "1" | "true" | "yes" | "on"))
+    .unwrap_or(false)
+}
+
+/// Per-environment overrides read from `config.<env>.json` in the app config directory, where
+/// `<env>` comes from `PLUTODUCK_ENV` (default `prod`). A missing file keeps the built-in defaults.
+#[derive(Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct EnvConfig {
+  pub port: Option<u16>,
+  /// Interface the backend binds; also used to reach it unless it is a wildcard address.
+  pub host: Option<String>,
+  pub frontend_url: Option<String>,
+  /// Dev server for debug builds, when it isn't on Tauri's configured `devUrl`.
+  pub dev_url: Option<String>,
+  /// Extra environment variables for the backend process.
+  pub backend_env: HashMap<String, String>,
+  /// Like `backend_env`, for tokens and keys: handed over through a private file rather than
+  /// the process environment; see `secrets`.
+  pub backend_secrets: HashMap<String, String>,
+  /// Exposed to the frontend as `window.__plutoFeatureFlags`.
+  pub feature_flags: Map<String, Value>,
+  /// Processes started next to the web server; see `workers`.
+  pub workers: Vec<WorkerConfig>,
+  /// Step that must succeed before the backend first starts; see `setup_script`.
+  pub setup: Option<SetupConfig>,
+}
+
+impl EnvConfig {
+  pub fn load(app: &AppHandle) -> Self {
+    let name = env_name();
+    let Ok(config_dir) = app.path().app_config_dir() else {
+      return Self::default();
+    };
+    let path = config_dir.join(format!("config.{name}.json"));
+    match read_env_config(&path) {
+      Some(config) => {
+        log::info!("loaded {name} environment config from {}", path.display());
+        config
+      }
+      None => Self::default(),
+    }
+  }
+
+  pub fn frontend_url(&self) -> Option<Url> {
+    let raw = self.frontend_url.as_deref()?;
+    Url::parse(raw)
+      .map_err(|err| log::warn!("ignoring invalid frontendUrl {raw:?}: {err}"))
+      .ok()
+  }
+}
+
+/// Where a debug build loads the frontend from: `PLUTODUCK_DEV_URL`, then `devUrl` in the
+/// env config, then Tauri's `build.devUrl`. Returns the URL with a label for its source.
+pub fn dev_url(app: &AppHandle) -> Option<(Url, &'static str)> {
+  let parse = |raw: &str, source: &'static str| {
+    Url::parse(raw.trim())
+      .map_err(|err| log::warn!("ignoring invalid dev URL {raw:?} from {source}: {err}"))
+      .ok()
+      .map(|url| (url, source))
+  };
+  std::env::var(DEV_URL_VAR)
+    .ok()
+    .filter(|value| !value.trim().is_empty())
+    .and_then(|value| parse(&value, DEV_URL_VAR))
+    .or_else(|| {
+      let config = app.state::<EnvConfig>();
+      config.dev_url.as_deref().and_then(|raw| parse(raw, "env config devUrl"))
+    })
+    .or_else(|| app.config().build.dev_url.clone().map(|url| (url, "tauri.conf.json devUrl")))
+}
+
+fn env_name() -> String {
+  let name = std::env::var(ENV_NAME_VAR).unwrap_or_default();
+  let name = name.trim().to_ascii_lowercase();
+  // The name becomes part of a file name, so anything beyond a plain identifier is rejected.
+  let valid = !name.is_empty()
+    && name
+      .bytes()
+      .all(|byte| byte.is_ascii_alphanumeric() || byte == b'-' || byte == b'_');
+  if valid {
+    name
+  } else {
+    if !name.is_empty() {
+      log::warn!("ignoring invalid {ENV_NAME_VAR}={name:?}; using {DEFAULT_ENV_NAME}");
+    }
+    DEFAULT_ENV_NAME.to_string()
+  }
+}
+
+fn read_env_config(path: &Path) -> Option<EnvConfig> {
+  let contents = std::fs::read_to_string(path).ok()?;
+  serde_json::from_str(&contents)
+    .map_err(|err| log::warn!("ignoring malformed {}: {err}", path.display()))
+    .ok()
+}
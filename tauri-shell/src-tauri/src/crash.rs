@@ -0,0 +1,240 @@
+use std::io::{Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::process::ExitStatus;
+use std::sync::OnceLock;
+
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager};
+
+use crate::preferences::{self, Preferences};
+use crate::{backend, diagnostics, logs};
+
+/// Present while a session is running; left behind when the app or backend died abnormally.
+pub const SESSION_MARKER: &str = "session.lock";
+pub const CRASHES_DIR: &str = "crashes";
+/// Structured reports of backend crashes, written only with the `crash_reports` preference on.
+pub const CRASH_REPORTS_DIR: &str = "crash-reports";
+const MAX_CRASH_SNAPSHOTS: usize = 5;
+const MAX_CRASH_REPORTS: usize = 20;
+const REPORT_LOG_LINES: usize = 200;
+/// Only the end of a log is read for the report, however large the file has grown.
+const REPORT_LOG_TAIL_BYTES: u64 = 256 * 1024;
+
+/// Identifies this run of the app in crash reports, so several backend crashes in one session
+/// can be told apart from crashes across launches.
+static SESSION_ID: OnceLock<String> = OnceLock::new();
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CrashReport {
+  pub id: String,
+  pub created_at: String,
+  pub session_id: String,
+  /// `None` when the process was killed by a signal.
+  pub exit_code: Option<i32>,
+  pub exit_status: String,
+  pub app_version: String,
+  pub tauri_version: String,
+  pub backend_version: Option<String>,
+  pub stdout_tail: Vec<String>,
+  pub stderr_tail: Vec<String>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CrashReportSummary {
+  pub id: String,
+  pub created_at: String,
+  pub exit_code: Option<i32>,
+  pub exit_status: String,
+}
+
+pub fn crashes_dir(data_root: &Path) -> PathBuf {
+  data_root.join(CRASHES_DIR)
+}
+
+/// Copies the previous session's logs aside if it didn't end cleanly, before `launch`
+/// truncates them, then marks the new session as running.
+pub fn begin_session(data_root: &Path, log_dir: &Path) {
+  let marker = data_root.join(SESSION_MARKER);
+  if marker.exists() {
+    match snapshot_logs(data_root, log_dir) {
+      Ok(Some(dir)) => warn!("previous session ended abnormally; logs preserved in {}", dir.display()),
+      Ok(None) => {}
+      Err(err) => warn!("failed to preserve crash logs: {err}"),
+    }
+    prune_snapshots(data_root);
+  }
+  if let Err(err) = std::fs::write(&marker, std::process::id().to_string()) {
+    warn!("failed to write session marker: {err}");
+  }
+}
+
+/// Clears the session marker; skipped when the backend exited with a failure so the
+/// next launch keeps its logs.
+pub fn end_session(data_root: &Path) {
+  let marker = data_root.join(SESSION_MARKER);
+  if marker.exists() {
+    if let Err(err) = std::fs::remove_file(&marker) {
+      warn!("failed to remove session marker: {err}");
+    }
+  }
+}
+
+fn snapshot_logs(data_root: &Path, log_dir: &Path) -> std::io::Result<Option<PathBuf>> {
+  let existing: Vec<PathBuf> = logs::LOG_FILES
+    .iter()
+    .map(|name| log_dir.join(name))
+    .filter(|path| path.is_file())
+    .collect();
+  if existing.is_empty() {
+    return Ok(None);
+  }
+
+  let stamp = chrono::Local::now().format("%Y%m%d-%H%M%S").to_string();
+  let dir = crashes_dir(data_root).join(stamp);
+  std::fs::create_dir_all(&dir)?;
+  for path in existing {
+    if let Some(name) = path.file_name() {
+      std::fs::copy(&path, dir.join(name))?;
+    }
+  }
+  Ok(Some(dir))
+}
+
+fn prune_snapshots(data_root: &Path) {
+  let Ok(entries) = std::fs::read_dir(crashes_dir(data_root)) else {
+    return;
+  };
+  // Snapshot names are sortable timestamps, so lexical order is chronological.
+  let mut snapshots: Vec<PathBuf> = entries
+    .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+    .filter(|path| path.is_dir())
+    .collect();
+  snapshots.sort();
+  let excess = snapshots.len().saturating_sub(MAX_CRASH_SNAPSHOTS);
+  for old in snapshots.into_iter().take(excess) {
+    info!("removing old crash snapshot {}", old.display());
+    let _ = std::fs::remove_dir_all(old);
+  }
+}
+
+pub fn reports_dir(data_root: &Path) -> PathBuf {
+  data_root.join(CRASH_REPORTS_DIR)
+}
+
+/// Writes a local crash report for a backend that exited on its own. Nothing leaves the
+/// machine; the frontend lists the reports and the user decides what to share.
+pub fn record_report(app: &AppHandle, status: &ExitStatus) {
+  let enabled = app
+    .state::<Preferences>()
+    .get_bool(preferences::CRASH_REPORTS, false);
+  if !enabled {
+    return;
+  }
+  let data_root = backend::resolve_data_root(app);
+  let log_dir = logs::log_dir(&data_root);
+  let now = chrono::Local::now();
+  let id = format!("{}-{}", now.format("%Y%m%d-%H%M%S"), now.timestamp_subsec_millis());
+  let report = CrashReport {
+    id: id.clone(),
+    created_at: now.to_rfc3339(),
+    session_id: SESSION_ID
+      .get_or_init(|| uuid::Uuid::new_v4().to_string())
+      .clone(),
+    exit_code: status.code(),
+    exit_status: status.to_string(),
+    app_version: app.package_info().version.to_string(),
+    tauri_version: tauri::VERSION.to_string(),
+    backend_version: diagnostics::read_version_file(app),
+    stdout_tail: tail_lines(&log_dir.join(logs::STDOUT_LOG)),
+    stderr_tail: tail_lines(&log_dir.join(logs::STDERR_LOG)),
+  };
+
+  let dir = reports_dir(&data_root);
+  let written = std::fs::create_dir_all(&dir).and_then(|()| {
+    let serialized = serde_json::to_vec_pretty(&report)?;
+    std::fs::write(dir.join(format!("{id}.json")), serialized)
+  });
+  match written {
+    Ok(()) => info!("backend crash report {id} written"),
+    Err(err) => warn!("failed to write crash report: {err}"),
+  }
+  prune_reports(&dir);
+}
+
+/// The last lines of a log file, reading at most its final 256 KiB.
+pub fn tail_lines(path: &Path) -> Vec<String> {
+  let Ok(mut file) = std::fs::File::open(path) else {
+    return Vec::new();
+  };
+  let len = file.metadata().map(|metadata| metadata.len()).unwrap_or(0);
+  let start = len.saturating_sub(REPORT_LOG_TAIL_BYTES);
+  let mut contents = Vec::new();
+  if file.seek(SeekFrom::Start(start)).is_err() || file.read_to_end(&mut contents).is_err() {
+    return Vec::new();
+  }
+  let contents = String::from_utf8_lossy(&contents);
+  let mut lines: Vec<&str> = contents.lines().collect();
+  // A read starting mid-file most likely cut the first line.
+  if start > 0 && !lines.is_empty() {
+    lines.remove(0);
+  }
+  let skip = lines.len().saturating_sub(REPORT_LOG_LINES);
+  lines.into_iter().skip(skip).map(str::to_string).collect()
+}
+
+fn prune_reports(dir: &Path) {
+  let mut reports = report_paths(dir);
+  let excess = reports.len().saturating_sub(MAX_CRASH_REPORTS);
+  for old in reports.drain(..excess) {
+    let _ = std::fs::remove_file(old);
+  }
+}
+
+/// Report files oldest first; ids are sortable timestamps.
+fn report_paths(dir: &Path) -> Vec<PathBuf> {
+  let Ok(entries) = std::fs::read_dir(dir) else {
+    return Vec::new();
+  };
+  let mut paths: Vec<PathBuf> = entries
+    .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+    .filter(|path| path.extension().is_some_and(|extension| extension == "json"))
+    .collect();
+  paths.sort();
+  paths
+}
+
+fn read_report(path: &Path) -> Result<CrashReport, String> {
+  let contents =
+    std::fs::read(path).map_err(|err| format!("Failed to read crash report: {err}"))?;
+  serde_json::from_slice(&contents).map_err(|err| format!("Malformed crash report: {err}"))
+}
+
+/// Crash reports newest first.
+#[tauri::command]
+pub fn list_crash_reports(app: AppHandle) -> Vec<CrashReportSummary> {
+  let dir = reports_dir(&backend::resolve_data_root(&app));
+  report_paths(&dir)
+    .iter()
+    .rev()
+    .filter_map(|path| read_report(path).ok())
+    .map(|report| CrashReportSummary {
+      id: report.id,
+      created_at: report.created_at,
+      exit_code: report.exit_code,
+      exit_status: report.exit_status,
+    })
+    .collect()
+}
+
+#[tauri::command]
+pub fn read_crash_report(app: AppHandle, id: String) -> Result<CrashReport, String> {
+  // Ids are generated timestamps; anything else could point outside the reports directory.
+  if id.is_empty() || !id.bytes().all(|byte| byte.is_ascii_digit() || byte == b'-') {
+    return Err(format!("Invalid crash report id {id:?}"));
+  }
+  let dir = reports_dir(&backend::resolve_data_root(&app));
+  read_report(&dir.join(format!("{id}.json")))
+}
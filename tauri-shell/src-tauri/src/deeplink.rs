@@ -0,0 +1,356 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Manager, State, WebviewWindow};
+
+use crate::readiness::{self, AppMounted};
+use crate::{developer, tasks};
+
+/// Must match `plugins.deep-link.desktop.schemes` in tauri.conf.json.
+const DEEP_LINK_SCHEME: &str = "plutoduck";
+/// How long a registration failure waits for the frontend to mount before being emitted.
+const REGISTRATION_REPORT_WAIT: Duration = Duration::from_secs(30);
+/// Unacknowledged callbacks kept for re-delivery; a frontend that never acks can't grow the
+/// queue without bound, and an OAuth callback this old is long expired anyway.
+const MAX_PENDING_CALLBACKS: usize = 20;
+
+static NEXT_CALLBACK_SEQ: AtomicU64 = AtomicU64::new(1);
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeepLinkStatus {
+  scheme: &'static str,
+  /// Whether the OS routes `scheme` links to this executable.
+  registered: bool,
+  /// What the links open instead, when the OS says: an executable or app bundle path, or on
+  /// Linux the desktop file name if its `Exec` line can't be read.
+  owner: Option<String>,
+}
+
+#[derive(Clone, Serialize)]
+struct RegistrationFailure {
+  scheme: &'static str,
+  error: String,
+}
+
+/// One OS-delivered auth callback. `id` is the idempotency key: the same callback arrives
+/// again through the DOM events and `pending_auth_callbacks` until the frontend calls
+/// `ack_deeplink` with it, and the frontend dedupes on it.
+#[derive(Clone, Serialize)]
+pub struct AuthCallback {
+  pub id: String,
+  pub url: String,
+}
+
+impl AuthCallback {
+  fn new(url: String) -> Self {
+    let seq = NEXT_CALLBACK_SEQ.fetch_add(1, Ordering::SeqCst);
+    Self {
+      id: format!("{}-{seq}", chrono::Utc::now().timestamp_millis()),
+      url,
+    }
+  }
+}
+
+/// Auth callbacks received from the OS that the frontend hasn't acknowledged yet. Survives
+/// page reloads and route changes, unlike `window.__plutoAuthCallbackQueue`, so it is the
+/// authoritative source on mount.
+#[derive(Default)]
+pub struct AuthCallbackQueue(Mutex<Vec<AuthCallback>>);
+
+/// Hands auth callback URLs to the frontend as one batch: raises the main window, queues them
+/// Rust-side and on `window.__plutoAuthCallbackQueue`, and fires the DOM events from a single
+/// eval so a page reinitializing between calls can't drop part of the batch. The Rust-side
+/// copy is written first, so a push lost to a reload is still drained on the next mount.
+pub fn dispatch_auth_callback(app: &AppHandle, urls: &[String]) {
+  if urls.is_empty() {
+    return;
+  }
+  let callbacks: Vec<AuthCallback> = urls.iter().cloned().map(AuthCallback::new).collect();
+  if let Ok(mut queue) = app.state::<AuthCallbackQueue>().0.lock() {
+    queue.extend(callbacks.iter().cloned());
+    let overflow = queue.len().saturating_sub(MAX_PENDING_CALLBACKS);
+    if overflow > 0 {
+      log::warn!("dropping {overflow} unacknowledged auth callback(s)");
+      queue.drain(..overflow);
+    }
+  }
+
+  let Some(window) = crate::show_and_focus_main_window(app) else {
+    log::warn!("auth callback received without a main window; queued {} URL(s)", urls.len());
+    return;
+  };
+  deliver(&window, &callbacks, true);
+}
+
+/// Delivers the still-unacknowledged callbacks again once the frontend reports it mounted,
+/// for a page that reloaded or swapped routes before handling them. The window isn't raised,
+/// and only the id-carrying events fire: a frontend reading the id-less
+/// `__plutoAuthCallbackQueue` would otherwise replay codes it already handled.
+pub fn redeliver_pending(app: &AppHandle) {
+  let pending = app
+    .state::<AuthCallbackQueue>()
+    .0
+    .lock()
+    .map(|queue| queue.clone())
+    .unwrap_or_default();
+  if pending.is_empty() {
+    return;
+  }
+  let Some(window) = app.get_webview_window("main") else {
+    return;
+  };
+  log::info!("re-delivering {} unacknowledged auth callback(s)", pending.len());
+  deliver(&window, &pending, false);
+}
+
+/// `queue_urls` also appends the URLs to `window.__plutoAuthCallbackQueue`, for a page that
+/// hasn't attached its listener yet; only done on first delivery.
+fn deliver(window: &WebviewWindow, callbacks: &[AuthCallback], queue_urls: bool) {
+  if let Ok(serialized) = serde_json::to_string(callbacks) {
+    let script = format!(
+      "(function (callbacks, queueUrls) {{var urls = callbacks.map(function (callback) {{ return callback.url; }});if (queueUrls) {{window.__plutoAuthCallbackQueue = window.__plutoAuthCallbackQueue || [];Array.prototype.push.apply(window.__plutoAuthCallbackQueue, urls);}}window.dispatchEvent(new CustomEvent('pluto-auth-callback-batch', {{ detail: {{ urls: urls, callbacks: callbacks }} }}));callbacks.forEach(function (callback) {{ window.dispatchEvent(new CustomEvent('pluto-auth-callback', {{ detail: callback }})); }});}})({0}, {queue_urls});",
+      serialized
+    );
+    let _ = window.eval(&script);
+  }
+}
+
+/// Checks the OS actually routes `plutoduck://` links to this app, since otherwise OAuth
+/// callbacks silently never arrive. Linux gets a runtime registration attempt; macOS
+/// registers through Info.plist at install time and has nothing to query. A failure is logged
+/// and emitted as `deeplink-registration-failed` once the frontend is listening.
+pub fn verify_registration(app: &AppHandle) {
+  let app = app.clone();
+  tasks::spawn("deeplink-registration-check", move || {
+    let Err(error) = check_registration(&app) else {
+      return;
+    };
+    log::error!("{DEEP_LINK_SCHEME}:// is not registered; OAuth login will not work: {error}");
+    app.state::<AppMounted>().wait(REGISTRATION_REPORT_WAIT);
+    if readiness::shutdown_requested() {
+      return;
+    }
+    let _ = app.emit(
+      "deeplink-registration-failed",
+      RegistrationFailure {
+        scheme: DEEP_LINK_SCHEME,
+        error,
+      },
+    );
+  });
+}
+
+#[cfg(any(target_os = "linux", windows))]
+fn check_registration(app: &AppHandle) -> Result<(), String> {
+  use tauri_plugin_deep_link::DeepLinkExt;
+
+  let deep_link = app.deep_link();
+  if deep_link.is_registered(DEEP_LINK_SCHEME).unwrap_or(false) {
+    return Ok(());
+  }
+  // Windows installers write the registry entry themselves; a missing one means a broken
+  // install rather than something to patch up at runtime.
+  if cfg!(target_os = "linux") {
+    log::warn!("{DEEP_LINK_SCHEME}:// handler missing; registering it");
+    deep_link
+      .register(DEEP_LINK_SCHEME)
+      .map_err(|err| format!("registration failed: {err}"))?;
+    if deep_link.is_registered(DEEP_LINK_SCHEME).unwrap_or(false) {
+      return Ok(());
+    }
+  }
+  Err("the scheme has no handler pointing at this executable".to_string())
+}
+
+#[cfg(not(any(target_os = "linux", windows)))]
+fn check_registration(_app: &AppHandle) -> Result<(), String> {
+  Ok(())
+}
+
+/// Reports who handles `plutoduck://` links, for the settings diagnostic that warns when
+/// login redirects won't reach the app.
+#[tauri::command]
+pub async fn deeplink_scheme_status(app: AppHandle) -> DeepLinkStatus {
+  let owner = scheme_owner();
+  DeepLinkStatus {
+    scheme: DEEP_LINK_SCHEME,
+    registered: is_registered_to_us(&app, owner.as_deref()),
+    owner,
+  }
+}
+
+#[cfg(any(target_os = "linux", windows))]
+fn is_registered_to_us(app: &AppHandle, _owner: Option<&str>) -> bool {
+  use tauri_plugin_deep_link::DeepLinkExt;
+
+  app.deep_link().is_registered(DEEP_LINK_SCHEME).unwrap_or(false)
+}
+
+#[cfg(not(any(target_os = "linux", windows)))]
+fn is_registered_to_us(_app: &AppHandle, owner: Option<&str>) -> bool {
+  let (Some(owner), Ok(exe)) = (owner, std::env::current_exe()) else {
+    return false;
+  };
+  let owner = std::fs::canonicalize(owner).unwrap_or_else(|_| owner.into());
+  exe.canonicalize().unwrap_or(exe).starts_with(owner)
+}
+
+/// The desktop file `xdg-mime` picks for the scheme, resolved to its `Exec` program.
+#[cfg(target_os = "linux")]
+fn scheme_owner() -> Option<String> {
+  let output = std::process::Command::new("xdg-mime")
+    .args(["query", "default", &format!("x-scheme-handler/{DEEP_LINK_SCHEME}")])
+    .output()
+    .ok()?;
+  let desktop_file = String::from_utf8_lossy(&output.stdout).trim().to_string();
+  if desktop_file.is_empty() {
+    return None;
+  }
+  let data_home = std::env::var_os("XDG_DATA_HOME")
+    .map(std::path::PathBuf::from)
+    .or_else(|| {
+      std::env::var_os("HOME").map(|home| std::path::Path::new(&home).join(".local/share"))
+    });
+  let data_dirs = std::env::var("XDG_DATA_DIRS")
+    .ok()
+    .filter(|dirs| !dirs.is_empty())
+    .unwrap_or_else(|| "/usr/local/share:/usr/share".to_string());
+  let exec = data_home
+    .into_iter()
+    .chain(data_dirs.split(':').map(std::path::PathBuf::from))
+    .find_map(|dir| std::fs::read_to_string(dir.join("applications").join(&desktop_file)).ok())
+    .and_then(|contents| {
+      contents
+        .lines()
+        .find_map(|line| line.strip_prefix("Exec="))
+        .and_then(first_command_word)
+    });
+  Some(exec.unwrap_or(desktop_file))
+}
+
+/// The program in the scheme's `shell\open\command` registry entry.
+#[cfg(windows)]
+fn scheme_owner() -> Option<String> {
+  use windows_sys::Win32::Foundation::ERROR_SUCCESS;
+  use windows_sys::Win32::System::Registry::{RegGetValueW, HKEY_CLASSES_ROOT, RRF_RT_REG_SZ};
+
+  let key: Vec<u16> = format!("{DEEP_LINK_SCHEME}\\shell\\open\\command")
+    .encode_utf16()
+    .chain(Some(0))
+    .collect();
+  let mut size = 0u32;
+  // SAFETY: `key` is NUL-terminated; a null data pointer only asks for the value's size.
+  let status = unsafe {
+    RegGetValueW(
+      HKEY_CLASSES_ROOT,
+      key.as_ptr(),
+      std::ptr::null(),
+      RRF_RT_REG_SZ,
+      std::ptr::null_mut(),
+      std::ptr::null_mut(),
+      &mut size,
+    )
+  };
+  if status != ERROR_SUCCESS {
+    return None;
+  }
+  let mut buffer = vec![0u16; (size as usize).div_ceil(2)];
+  // SAFETY: `buffer` holds `size` bytes, as reported by the call above.
+  let status = unsafe {
+    RegGetValueW(
+      HKEY_CLASSES_ROOT,
+      key.as_ptr(),
+      std::ptr::null(),
+      RRF_RT_REG_SZ,
+      std::ptr::null_mut(),
+      buffer.as_mut_ptr().cast(),
+      &mut size,
+    )
+  };
+  if status != ERROR_SUCCESS {
+    return None;
+  }
+  let len = buffer.iter().position(|&unit| unit == 0).unwrap_or(buffer.len());
+  first_command_word(&String::from_utf16_lossy(&buffer[..len]))
+}
+
+/// The app bundle Launch Services opens the scheme with.
+#[cfg(target_os = "macos")]
+fn scheme_owner() -> Option<String> {
+  use cocoa::base::{id, nil};
+  use cocoa::foundation::NSString;
+  use objc::{class, msg_send, sel, sel_impl};
+
+  // SAFETY: plain Foundation/AppKit calls; every object is nil-checked before use.
+  unsafe {
+    let link = NSString::alloc(nil).init_str(&format!("{DEEP_LINK_SCHEME}://"));
+    let url: id = msg_send![class!(NSURL), URLWithString: link];
+    let _: () = msg_send![link, release];
+    if url == nil {
+      return None;
+    }
+    let workspace: id = msg_send![class!(NSWorkspace), sharedWorkspace];
+    let app_url: id = msg_send![workspace, URLForApplicationToOpenURL: url];
+    if app_url == nil {
+      return None;
+    }
+    let path: id = msg_send![app_url, path];
+    if path == nil {
+      return None;
+    }
+    let path = std::ffi::CStr::from_ptr(path.UTF8String());
+    Some(path.to_string_lossy().into_owned())
+  }
+}
+
+#[cfg(not(any(target_os = "linux", windows, target_os = "macos")))]
+fn scheme_owner() -> Option<String> {
+  None
+}
+
+/// The program of a command line such as `"C:\…\app.exe" "%1"` or `/usr/bin/app %u`.
+#[cfg(any(target_os = "linux", windows))]
+fn first_command_word(command: &str) -> Option<String> {
+  let command = command.trim();
+  let program = match command.strip_prefix('"') {
+    Some(quoted) => quoted.split('"').next()?,
+    None => command.split_whitespace().next()?,
+  };
+  (!program.is_empty()).then(|| program.to_string())
+}
+
+/// Returns every callback not yet acknowledged with [`ack_deeplink`]; the frontend calls this
+/// on mount. Nothing is removed, so one whose handler was cut short by a navigation is
+/// returned again next time.
+#[tauri::command]
+pub fn pending_auth_callbacks(queue: State<'_, AuthCallbackQueue>) -> Vec<AuthCallback> {
+  queue
+    .0
+    .lock()
+    .map(|pending| pending.clone())
+    .unwrap_or_default()
+}
+
+/// Marks the callback with `id` as handled so it is no longer re-delivered. Returns whether it
+/// was still pending; acking twice is harmless.
+#[tauri::command]
+pub fn ack_deeplink(queue: State<'_, AuthCallbackQueue>, id: String) -> bool {
+  let Ok(mut pending) = queue.0.lock() else {
+    return false;
+  };
+  let before = pending.len();
+  pending.retain(|callback| callback.id != id);
+  pending.len() != before
+}
+
+/// Developer-only: runs the same dispatch path as an OS-delivered deep link.
+#[tauri::command]
+pub fn simulate_auth_callback(app: AppHandle, url: String) -> Result<(), String> {
+  developer::ensure_enabled(&app)?;
+  log::info!("simulating auth callback: {url}");
+  dispatch_auth_callback(&app, &[url]);
+  Ok(())
+}
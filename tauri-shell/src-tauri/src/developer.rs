@@ -0,0 +1,63 @@
+use serde_json::Value;
+use tauri::{AppHandle, Manager};
+use tauri_plugin_dialog::{DialogExt, MessageDialogButtons, MessageDialogKind};
+
+use crate::dialogs::OpenDialog;
+use crate::preferences::{self, Preferences};
+
+/// Debug builds always have developer tooling; release builds need the `developer_mode` preference.
+pub fn is_enabled(app: &AppHandle) -> bool {
+  cfg!(debug_assertions) || app.state::<Preferences>().get_bool(preferences::DEVELOPER_MODE, false)
+}
+
+/// Shared guard for commands that are only meant for debugging.
+pub fn ensure_enabled(app: &AppHandle) -> Result<(), String> {
+  if is_enabled(app) {
+    Ok(())
+  } else {
+    Err("Developer mode required".to_string())
+  }
+}
+
+/// Whether guarded commands are available, counting debug builds where the preference is moot.
+#[tauri::command]
+pub fn developer_mode(app: AppHandle) -> bool {
+  is_enabled(&app)
+}
+
+/// Unlocks (or locks again) the commands behind [`ensure_enabled`] in release builds. Turning
+/// it on needs the user to confirm in a native dialog, which page script can't click through.
+/// Returns whether developer mode was changed.
+#[tauri::command]
+pub async fn set_developer_mode(app: AppHandle, enabled: bool) -> Result<bool, String> {
+  if enabled && !is_enabled(&app) && !confirm_enable(&app) {
+    log::info!("enabling developer mode was declined");
+    return Ok(false);
+  }
+  app
+    .state::<Preferences>()
+    .set(preferences::DEVELOPER_MODE, Value::Bool(enabled))?;
+  log::info!("developer mode {}", if enabled { "enabled" } else { "disabled" });
+  Ok(true)
+}
+
+fn confirm_enable(app: &AppHandle) -> bool {
+  let _open = OpenDialog::track();
+  let mut dialog = app
+    .dialog()
+    .message(
+      "Developer mode unlocks debugging commands that can read logs, pause the backend and \
+       change how it runs. Only turn it on if you were asked to by support or are developing \
+       Pluto Duck.",
+    )
+    .title("Turn on developer mode?")
+    .kind(MessageDialogKind::Warning)
+    .buttons(MessageDialogButtons::OkCancelCustom(
+      "Turn On".to_string(),
+      "Cancel".to_string(),
+    ));
+  if let Some(window) = app.get_webview_window("main") {
+    dialog = dialog.parent(&window);
+  }
+  dialog.blocking_show()
+}
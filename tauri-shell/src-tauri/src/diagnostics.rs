@@ -0,0 +1,218 @@
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager, Url};
+
+use crate::{api, backend, readiness};
+
+const BACKEND_VERSION_FILE: &str = "version.json";
+const BACKEND_VERSION_TIMEOUT: Duration = Duration::from_secs(2);
+const MAX_CONNECTION_TIMEOUT_MS: u64 = 60_000;
+/// Lives in the data root, so resetting the data also resets the ID.
+const INSTALL_ID_FILE: &str = "install-id";
+
+/// Used for the rest of the session if the ID file can't be written.
+static FALLBACK_INSTALL_ID: OnceLock<String> = OnceLock::new();
+/// The OS and hardware don't change while we run, so they are read once.
+static SYSTEM_INFO: OnceLock<SystemInfo> = OnceLock::new();
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Versions {
+  pub app: String,
+  pub tauri: String,
+  pub backend: Option<String>,
+}
+
+#[derive(Default)]
+pub struct VersionsCache(Mutex<Option<Versions>>);
+
+#[derive(Deserialize)]
+struct VersionPayload {
+  version: String,
+}
+
+/// Returns the shell, Tauri and backend versions for the About dialog and bug reports.
+///
+/// The result is cached once the backend version has been resolved; until then each
+/// call retries so an About dialog opened during startup doesn't pin `backend: null`.
+#[tauri::command]
+pub async fn versions(app: AppHandle) -> Result<Versions, String> {
+  let cache = app.state::<VersionsCache>();
+  if let Some(cached) = cache.0.lock().map_err(|err| err.to_string())?.clone() {
+    return Ok(cached);
+  }
+
+  let backend = match read_version_file(&app) {
+    Some(version) => Some(version),
+    None => fetch_backend_version().await,
+  };
+  let versions = Versions {
+    app: app.package_info().version.to_string(),
+    tauri: tauri::VERSION.to_string(),
+    backend,
+  };
+
+  if versions.backend.is_some() {
+    *cache.0.lock().map_err(|err| err.to_string())? = Some(versions.clone());
+  }
+  Ok(versions)
+}
+
+/// The backend version shipped next to its binary, if any.
+pub fn read_version_file(app: &AppHandle) -> Option<String> {
+  let binary = backend::backend_binary_path(app).ok()?;
+  let contents = std::fs::read_to_string(binary.parent()?.join(BACKEND_VERSION_FILE)).ok()?;
+  let payload: VersionPayload = serde_json::from_str(&contents)
+    .map_err(|err| log::warn!("ignoring malformed {BACKEND_VERSION_FILE}: {err}"))
+    .ok()?;
+  Some(payload.version)
+}
+
+async fn fetch_backend_version() -> Option<String> {
+  let response = api::client(BACKEND_VERSION_TIMEOUT)
+    .ok()?
+    .get(backend::health_url())
+    .send()
+    .await
+    .and_then(|response| response.error_for_status())
+    .map_err(|err| log::warn!("backend version probe failed: {err}"))
+    .ok()?;
+  let payload: VersionPayload = response.json().await.ok()?;
+  Some(payload.version)
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BuildInfo {
+  pub profile: &'static str,
+  pub commit: &'static str,
+  /// RFC 3339, or `None` if the build script couldn't read the clock.
+  pub built_at: Option<String>,
+  pub target: &'static str,
+}
+
+/// Identifies the exact build for support: profile, commit, build time and target triple,
+/// all captured by `build.rs`.
+#[tauri::command]
+pub fn build_info() -> BuildInfo {
+  let built_at = env!("PLUTODUCK_BUILD_TIMESTAMP")
+    .parse::<i64>()
+    .ok()
+    .filter(|seconds| *seconds > 0)
+    .and_then(|seconds| chrono::DateTime::from_timestamp(seconds, 0))
+    .map(|timestamp| timestamp.to_rfc3339());
+  BuildInfo {
+    profile: if cfg!(debug_assertions) { "debug" } else { "release" },
+    commit: env!("PLUTODUCK_GIT_HASH"),
+    built_at,
+    target: env!("PLUTODUCK_BUILD_TARGET"),
+  }
+}
+
+/// Random UUID identifying this install's data, created on first use and kept across launches
+/// and updates. Only ever handed to the frontend, which decides (with consent) what to do with it.
+#[tauri::command]
+pub fn install_id(app: AppHandle) -> String {
+  let path = backend::resolve_data_root(&app).join(INSTALL_ID_FILE);
+  if let Ok(contents) = std::fs::read_to_string(&path) {
+    if let Ok(id) = uuid::Uuid::parse_str(contents.trim()) {
+      return id.to_string();
+    }
+    log::warn!("{} is malformed; generating a new install ID", path.display());
+  }
+
+  let id = uuid::Uuid::new_v4().to_string();
+  match std::fs::write(&path, &id) {
+    Ok(()) => id,
+    Err(err) => {
+      log::warn!("failed to persist install ID to {}: {err}", path.display());
+      FALLBACK_INSTALL_ID.get_or_init(|| id).clone()
+    }
+  }
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SystemInfo {
+  pub os_name: Option<String>,
+  pub os_version: Option<String>,
+  pub kernel_version: Option<String>,
+  pub arch: String,
+  pub total_memory_bytes: u64,
+  pub cpu_model: Option<String>,
+  pub physical_cores: Option<usize>,
+  pub logical_cores: usize,
+}
+
+/// OS and hardware summary for support, so performance reports can be matched to the machine.
+/// GPU details aren't included; `sysinfo` has no portable way to read them.
+#[tauri::command]
+pub async fn system_info() -> SystemInfo {
+  SYSTEM_INFO.get_or_init(read_system_info).clone()
+}
+
+fn read_system_info() -> SystemInfo {
+  use sysinfo::{CpuRefreshKind, System};
+
+  let mut system = System::new();
+  system.refresh_memory();
+  system.refresh_cpu_list(CpuRefreshKind::nothing());
+  let cpu_model = system
+    .cpus()
+    .first()
+    .map(|cpu| cpu.brand().trim().to_string())
+    .filter(|brand| !brand.is_empty());
+  SystemInfo {
+    os_name: System::name(),
+    os_version: System::long_os_version().or_else(System::os_version),
+    kernel_version: System::kernel_version(),
+    arch: System::cpu_arch(),
+    total_memory_bytes: system.total_memory(),
+    cpu_model,
+    physical_cores: System::physical_core_count(),
+    logical_cores: system.cpus().len(),
+  }
+}
+
+/// Time from process start to the frontend reporting it is interactive, for tracking startup
+/// regressions across releases. 0 until the frontend has called `notify_app_mounted`.
+#[tauri::command]
+pub fn startup_duration_ms() -> u64 {
+  readiness::startup_duration_ms()
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConnectionResult {
+  pub status: u16,
+  pub elapsed_ms: u64,
+}
+
+/// GETs an arbitrary http(s) URL for a settings "Test connection" button. Any HTTP response
+/// counts as reachable and reports its status; transport failures are errors. Unlike the
+/// loopback probes this request goes through the configured proxy.
+#[tauri::command]
+pub async fn test_connection(url: String, timeout_ms: u64) -> Result<ConnectionResult, String> {
+  let parsed = Url::parse(url.trim()).map_err(|err| format!("Invalid URL: {err}"))?;
+  if !matches!(parsed.scheme(), "http" | "https") {
+    return Err("Only http(s) URLs are allowed".to_string());
+  }
+  let timeout = Duration::from_millis(timeout_ms.clamp(1, MAX_CONNECTION_TIMEOUT_MS));
+  let client = reqwest::Client::builder()
+    .timeout(timeout)
+    .build()
+    .map_err(|err| format!("Failed to build HTTP client: {err}"))?;
+
+  let started = Instant::now();
+  let response = client
+    .get(parsed)
+    .send()
+    .await
+    .map_err(|err| format!("Connection failed: {err}"))?;
+  Ok(ConnectionResult {
+    status: response.status().as_u16(),
+    elapsed_ms: u64::try_from(started.elapsed().as_millis()).unwrap_or(u64::MAX),
+  })
+}
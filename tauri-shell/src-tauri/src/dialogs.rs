@@ -0,0 +1,126 @@
+//! Typed wrappers around the native file dialogs, with the extension groups the app reuses.
+
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use serde::Deserialize;
+use tauri::{AppHandle, Runtime};
+use tauri_plugin_dialog::{DialogExt, FileDialogBuilder, FilePath};
+
+/// Extension groups shared by every feature that picks files.
+#[derive(Clone, Copy, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FilterPreset {
+  Preferences,
+  Database,
+  Archive,
+}
+
+impl FilterPreset {
+  fn name_and_extensions(self) -> (&'static str, &'static [&'static str]) {
+    match self {
+      Self::Preferences => ("Preferences", &["json"]),
+      Self::Database => ("DuckDB database", &["duckdb", "db"]),
+      Self::Archive => ("Zip archive", &["zip"]),
+    }
+  }
+}
+
+/// Either a preset name (`"database"`) or `{ name, extensions }`.
+#[derive(Deserialize)]
+#[serde(untagged)]
+pub enum FileFilter {
+  Preset(FilterPreset),
+  Custom { name: String, extensions: Vec<String> },
+}
+
+/// Native dialogs currently on screen; they take focus away from our windows.
+static OPEN_DIALOGS: AtomicUsize = AtomicUsize::new(0);
+
+/// Counts a dialog as open until dropped, so hide-on-blur leaves the window alone.
+pub struct OpenDialog(());
+
+impl OpenDialog {
+  pub fn track() -> Self {
+    OPEN_DIALOGS.fetch_add(1, Ordering::SeqCst);
+    Self(())
+  }
+}
+
+impl Drop for OpenDialog {
+  fn drop(&mut self) {
+    OPEN_DIALOGS.fetch_sub(1, Ordering::SeqCst);
+  }
+}
+
+pub fn any_open() -> bool {
+  OPEN_DIALOGS.load(Ordering::SeqCst) > 0
+}
+
+fn with_filters<R: Runtime>(
+  mut dialog: FileDialogBuilder<R>,
+  filters: &[FileFilter],
+) -> FileDialogBuilder<R> {
+  for filter in filters {
+    dialog = match filter {
+      FileFilter::Preset(preset) => {
+        let (name, extensions) = preset.name_and_extensions();
+        dialog.add_filter(name, extensions)
+      }
+      FileFilter::Custom { name, extensions } => {
+        let extensions: Vec<&str> = extensions.iter().map(String::as_str).collect();
+        dialog.add_filter(name, &extensions)
+      }
+    };
+  }
+  dialog
+}
+
+fn into_path(picked: Option<FilePath>) -> Result<Option<PathBuf>, String> {
+  picked
+    .map(|path| path.into_path().map_err(|err| err.to_string()))
+    .transpose()
+}
+
+/// Blocking open dialog; call from an async command so the main thread stays free.
+pub fn pick_file_path(app: &AppHandle, filters: &[FileFilter]) -> Result<Option<PathBuf>, String> {
+  let _open = OpenDialog::track();
+  into_path(with_filters(app.dialog().file(), filters).blocking_pick_file())
+}
+
+/// Blocking save dialog; call from an async command so the main thread stays free.
+pub fn save_file_path(
+  app: &AppHandle,
+  default_name: Option<&str>,
+  filters: &[FileFilter],
+) -> Result<Option<PathBuf>, String> {
+  let mut dialog = with_filters(app.dialog().file(), filters);
+  if let Some(name) = default_name {
+    dialog = dialog.set_file_name(name);
+  }
+  let _open = OpenDialog::track();
+  into_path(dialog.blocking_save_file())
+}
+
+#[tauri::command]
+pub async fn pick_file(
+  app: AppHandle,
+  filters: Option<Vec<FileFilter>>,
+) -> Result<Option<PathBuf>, String> {
+  pick_file_path(&app, &filters.unwrap_or_default())
+}
+
+#[tauri::command]
+pub async fn pick_directory(app: AppHandle) -> Result<Option<PathBuf>, String> {
+  let _open = OpenDialog::track();
+  into_path(app.dialog().file().blocking_pick_folder())
+}
+
+#[tauri::command]
+pub async fn save_file(
+  app: AppHandle,
+  default_name: Option<String>,
+  filters: Option<Vec<FileFilter>>,
+) -> Result<Option<PathBuf>, String> {
+  save_file_path(&app, default_name.as_deref(), &filters.unwrap_or_default())
+}
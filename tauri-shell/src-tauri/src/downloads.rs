@@ -0,0 +1,297 @@
+//! One download flow on every platform instead of each webview's own: files go to the
+//! configured download directory, or the user picks a location in the save dialog.
+
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use serde::Serialize;
+use serde_json::Value;
+use tauri::webview::DownloadEvent;
+use tauri::{AppHandle, Emitter, Manager, State, Url, Webview};
+
+use crate::preferences::{self, Preferences};
+use crate::{api, backend, dialogs, tasks};
+
+const FALLBACK_FILE_NAME: &str = "download";
+/// A backend that stops sending export data for this long is treated as failed.
+const EXPORT_IDLE_TIMEOUT: Duration = Duration::from_secs(60);
+const EXPORT_PROGRESS_INTERVAL: Duration = Duration::from_millis(250);
+
+#[derive(Clone, Serialize)]
+struct DownloadStarted {
+  url: String,
+  path: PathBuf,
+}
+
+#[derive(Clone, Serialize)]
+struct DownloadComplete {
+  url: String,
+  /// Not reported by WebKit on macOS for downloads the webview performed itself.
+  path: Option<PathBuf>,
+  success: bool,
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ExportProgress {
+  url: String,
+  dest: PathBuf,
+  received_bytes: u64,
+  /// From `Content-Length`; absent when the backend streams without one.
+  total_bytes: Option<u64>,
+  done: bool,
+}
+
+/// `on_download` handler for app windows. Returning false cancels the webview's download.
+pub fn handle(webview: Webview, event: DownloadEvent<'_>) -> bool {
+  let app = webview.app_handle();
+  match event {
+    DownloadEvent::Requested { url, destination } => {
+      let name = file_name(&url, destination);
+      if let Some(dir) = download_dir(app) {
+        *destination = unique_path(&dir, &name);
+        emit_started(app, &url, destination);
+        return true;
+      }
+      if matches!(url.scheme(), "http" | "https") {
+        // The dialog blocks and this runs on the main thread, so the shell re-fetches the
+        // file itself once a location is picked. Only the URL carries over, not cookies.
+        save_with_dialog(app.clone(), url, name);
+        return false;
+      }
+      // blob: and data: URLs only exist inside the page, so the webview has to finish
+      // these itself; they land in the system downloads folder.
+      let dir = app
+        .path()
+        .download_dir()
+        .unwrap_or_else(|_| std::env::temp_dir());
+      *destination = unique_path(&dir, &name);
+      emit_started(app, &url, destination);
+      true
+    }
+    DownloadEvent::Finished { url, path, success } => {
+      emit_complete(app, &url, path, success);
+      true
+    }
+    _ => true,
+  }
+}
+
+fn download_dir(app: &AppHandle) -> Option<PathBuf> {
+  let value = app.state::<Preferences>().get(preferences::DOWNLOAD_DIR)?;
+  let dir = PathBuf::from(value.as_str()?.trim());
+  if dir.as_os_str().is_empty() {
+    return None;
+  }
+  if !dir.is_dir() {
+    log::warn!("download directory {} is missing; asking instead", dir.display());
+    return None;
+  }
+  Some(dir)
+}
+
+/// The name the webview suggested, else the URL's last path segment.
+fn file_name(url: &Url, suggested: &Path) -> String {
+  suggested
+    .file_name()
+    .and_then(|name| name.to_str())
+    .map(str::to_string)
+    .or_else(|| {
+      url
+        .path_segments()
+        .and_then(|mut segments| segments.next_back())
+        .filter(|segment| !segment.is_empty())
+        .map(str::to_string)
+    })
+    .unwrap_or_else(|| FALLBACK_FILE_NAME.to_string())
+}
+
+/// `name`, or `name (1)`, `name (2)`… if something by that name already exists in `dir`.
+fn unique_path(dir: &Path, name: &str) -> PathBuf {
+  let candidate = dir.join(name);
+  if !candidate.exists() {
+    return candidate;
+  }
+  let path = Path::new(name);
+  let stem = path.file_stem().and_then(|stem| stem.to_str()).unwrap_or(name);
+  let extension = path.extension().and_then(|extension| extension.to_str());
+  (1..)
+    .map(|index| match extension {
+      Some(extension) => dir.join(format!("{stem} ({index}).{extension}")),
+      None => dir.join(format!("{stem} ({index})")),
+    })
+    .find(|candidate| !candidate.exists())
+    .unwrap_or(candidate)
+}
+
+fn save_with_dialog(app: AppHandle, url: Url, name: String) {
+  tasks::spawn("download", move || {
+    let path = match dialogs::save_file_path(&app, Some(&name), &[]) {
+      Ok(Some(path)) => path,
+      Ok(None) => return,
+      Err(err) => {
+        log::warn!("download save dialog failed: {err}");
+        return;
+      }
+    };
+    emit_started(&app, &url, &path);
+    let result = tauri::async_runtime::block_on(fetch_to(&url, &path));
+    if let Err(err) = &result {
+      log::warn!("download of {url} failed: {err}");
+      let _ = std::fs::remove_file(&path);
+    }
+    emit_complete(&app, &url, Some(path), result.is_ok());
+  });
+}
+
+/// Backend downloads go through the backend client, which skips the system proxy and trusts the
+/// local CA; anything else is fetched like a browser would.
+fn download_client(url: &Url) -> Result<reqwest::Client, String> {
+  if is_backend_url(url) {
+    api::streaming_client(EXPORT_IDLE_TIMEOUT)
+  } else {
+    Ok(reqwest::Client::new())
+  }
+}
+
+fn is_backend_url(url: &Url) -> bool {
+  Url::parse(&backend::origin()).is_ok_and(|backend| url.origin() == backend.origin())
+}
+
+async fn fetch_to(url: &Url, path: &Path) -> Result<(), String> {
+  let mut response = download_client(url)?
+    .get(url.clone())
+    .send()
+    .await
+    .and_then(|response| response.error_for_status())
+    .map_err(|err| err.to_string())?;
+  let mut file = std::fs::File::create(path).map_err(|err| err.to_string())?;
+  while let Some(chunk) = response.chunk().await.map_err(|err| err.to_string())? {
+    file.write_all(&chunk).map_err(|err| err.to_string())?;
+  }
+  Ok(())
+}
+
+fn emit_started(app: &AppHandle, url: &Url, path: &Path) {
+  log::info!("download started: {url} -> {}", path.display());
+  let payload = DownloadStarted {
+    url: url.to_string(),
+    path: path.to_path_buf(),
+  };
+  let _ = app.emit("download-started", payload);
+}
+
+fn emit_complete(app: &AppHandle, url: &Url, path: Option<PathBuf>, success: bool) {
+  log::info!("download finished: {url} (success: {success})");
+  let payload = DownloadComplete {
+    url: url.to_string(),
+    path,
+    success,
+  };
+  let _ = app.emit("download-complete", payload);
+}
+
+/// Sets where downloads go without asking; `None` goes back to asking every time.
+#[tauri::command]
+pub fn set_download_dir(
+  preferences: State<'_, Preferences>,
+  path: Option<PathBuf>,
+) -> Result<(), String> {
+  let value = match path {
+    Some(path) if !path.is_dir() => {
+      return Err(format!("{} is not a directory", path.display()));
+    }
+    Some(path) => path.to_string_lossy().into_owned(),
+    None => String::new(),
+  };
+  preferences.set(preferences::DOWNLOAD_DIR, Value::String(value))
+}
+
+/// Fetches an export from the local backend straight to `dest` in chunks, emitting
+/// `export-progress` along the way, so large exports never pass through the webview. The data
+/// goes to `dest` with a `.part` suffix first and is renamed once complete.
+#[tauri::command]
+pub async fn stream_export_to_file(
+  app: AppHandle,
+  url: String,
+  dest: PathBuf,
+) -> Result<(), String> {
+  let url = Url::parse(&url).map_err(|err| format!("Invalid export URL {url:?}: {err}"))?;
+  if !is_backend_url(&url) {
+    return Err(format!("{url} is not served by the local backend"));
+  }
+  let partial = dest.with_extension(match dest.extension().and_then(|ext| ext.to_str()) {
+    Some(extension) => format!("{extension}.part"),
+    None => "part".to_string(),
+  });
+  let result = stream_to(&app, &url, &partial, &dest).await;
+  if result.is_err() {
+    let _ = std::fs::remove_file(&partial);
+  }
+  result
+}
+
+async fn stream_to(app: &AppHandle, url: &Url, partial: &Path, dest: &Path) -> Result<(), String> {
+  let mut response = api::streaming_client(EXPORT_IDLE_TIMEOUT)?
+    .get(url.clone())
+    .send()
+    .await
+    .and_then(|response| response.error_for_status())
+    .map_err(|err| format!("Export request failed: {err}"))?;
+  let mut progress = ExportProgress {
+    url: url.to_string(),
+    dest: dest.to_path_buf(),
+    received_bytes: 0,
+    total_bytes: response.content_length(),
+    done: false,
+  };
+  let file = std::fs::File::create(partial)
+    .map_err(|err| format!("Failed to create {}: {err}", partial.display()))?;
+  let mut writer = std::io::BufWriter::new(file);
+  let mut last_emit = Instant::now();
+  while let Some(chunk) = response
+    .chunk()
+    .await
+    .map_err(|err| format!("Export download failed: {err}"))?
+  {
+    writer
+      .write_all(&chunk)
+      .map_err(|err| format!("Failed to write {}: {err}", partial.display()))?;
+    progress.received_bytes += chunk.len() as u64;
+    if last_emit.elapsed() >= EXPORT_PROGRESS_INTERVAL {
+      last_emit = Instant::now();
+      let _ = app.emit("export-progress", progress.clone());
+    }
+  }
+  writer
+    .into_inner()
+    .map_err(|err| err.to_string())
+    .and_then(|file| file.sync_all().map_err(|err| err.to_string()))
+    .map_err(|err| format!("Failed to write {}: {err}", partial.display()))?;
+  std::fs::rename(partial, dest)
+    .map_err(|err| format!("Failed to move export to {}: {err}", dest.display()))?;
+  log::info!("export {url} saved to {} ({} bytes)", dest.display(), progress.received_bytes);
+  progress.done = true;
+  let _ = app.emit("export-progress", progress);
+  Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn unique_path_numbers_taken_names() {
+    let dir = std::env::temp_dir().join(format!("pluto-duck-unique-path-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    assert_eq!(unique_path(&dir, "report.csv"), dir.join("report.csv"));
+
+    std::fs::write(dir.join("report.csv"), "").unwrap();
+    std::fs::write(dir.join("report (1).csv"), "").unwrap();
+    std::fs::write(dir.join("README"), "").unwrap();
+    assert_eq!(unique_path(&dir, "report.csv"), dir.join("report (2).csv"));
+    assert_eq!(unique_path(&dir, "README"), dir.join("README (1)"));
+    std::fs::remove_dir_all(&dir).unwrap();
+  }
+}
@@ -0,0 +1,96 @@
+use std::sync::OnceLock;
+
+use serde_json::{Map, Value};
+use tauri::{AppHandle, Manager};
+
+use crate::config::EnvConfig;
+
+pub const FEATURE_FLAGS_ENV: &str = "PLUTODUCK_FEATURE_FLAGS";
+const FLAG_ARG: &str = "--flag";
+
+/// `--flag` overrides from this launch's command line; see [`parse_cli_args`].
+static CLI_FLAGS: OnceLock<Map<String, Value>> = OnceLock::new();
+
+/// Collects every `--flag key=value` (or `--flag=key=value`) on the command line, for testers
+/// toggling a feature for one launch. Values that parse as JSON (`true`, `3`, `"x"`) keep
+/// their type, anything else is a string, and a bare `--flag key` means `true`. Keys aren't
+/// checked against anything, so the frontend sees flags it doesn't know about as given.
+/// Called once at the top of `run()`.
+pub fn parse_cli_args(args: impl IntoIterator<Item = String>) {
+  let mut flags = Map::new();
+  let mut args = args.into_iter();
+  while let Some(arg) = args.next() {
+    let spec = if arg == FLAG_ARG {
+      match args.next() {
+        Some(spec) => spec,
+        None => {
+          log::warn!("ignoring {FLAG_ARG} without a key=value");
+          continue;
+        }
+      }
+    } else if let Some(spec) = arg.strip_prefix("--flag=") {
+      spec.to_string()
+    } else {
+      continue;
+    };
+    let (key, value) = match spec.split_once('=') {
+      Some((key, raw)) => (
+        key,
+        serde_json::from_str(raw).unwrap_or_else(|_| Value::String(raw.to_string())),
+      ),
+      None => (spec.as_str(), Value::Bool(true)),
+    };
+    if key.trim().is_empty() {
+      log::warn!("ignoring {FLAG_ARG} {spec}: missing key");
+      continue;
+    }
+    flags.insert(key.trim().to_string(), value);
+  }
+  if !flags.is_empty() {
+    log::info!("feature flags from the command line: {}", Value::Object(flags.clone()));
+  }
+  let _ = CLI_FLAGS.set(flags);
+}
+
+fn cli_flags() -> Option<&'static Map<String, Value>> {
+  CLI_FLAGS.get().filter(|flags| !flags.is_empty())
+}
+
+fn env_flags() -> Map<String, Value> {
+  let Ok(raw) = std::env::var(FEATURE_FLAGS_ENV) else {
+    return Map::new();
+  };
+  match serde_json::from_str::<Value>(&raw) {
+    Ok(Value::Object(flags)) => flags,
+    _ => {
+      log::warn!("ignoring {FEATURE_FLAGS_ENV}: expected a JSON object");
+      Map::new()
+    }
+  }
+}
+
+/// Flags from the environment config's `featureFlags`, overlaid with `PLUTODUCK_FEATURE_FLAGS`
+/// (a JSON object), then with `--flag` arguments. Anything missing or malformed contributes
+/// nothing.
+pub fn resolve(app: &AppHandle) -> Map<String, Value> {
+  let mut flags = app.state::<EnvConfig>().feature_flags.clone();
+  flags.extend(env_flags());
+  flags.extend(cli_flags().cloned().unwrap_or_default());
+  flags
+}
+
+/// `PLUTODUCK_FEATURE_FLAGS` for the backend when `--flag` arguments were given: the
+/// inherited value with them laid on top. Without any the backend just inherits the variable.
+pub fn backend_env() -> Option<String> {
+  let cli = cli_flags()?;
+  let mut flags = env_flags();
+  flags.extend(cli.clone());
+  Some(Value::Object(flags).to_string())
+}
+
+/// Defines `window.__plutoFeatureFlags` before any page script runs, so features can be
+/// toggled without rebuilding the frontend.
+pub fn init_script(app: &AppHandle) -> String {
+  let serialized = serde_json::to_string(&resolve(app)).unwrap_or_else(|_| "{}".to_string());
+  format!("window.__plutoFeatureFlags = Object.freeze({serialized});")
+}
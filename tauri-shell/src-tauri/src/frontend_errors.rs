@@ -0,0 +1,83 @@
+use std::io::Write;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use tauri::AppHandle;
+
+use crate::{backend, logs};
+
+const RATE_WINDOW: Duration = Duration::from_secs(60);
+const MAX_REPORTS_PER_WINDOW: u32 = 20;
+/// The file is appended to across sessions; start over once it gets this big.
+const MAX_LOG_BYTES: u64 = 1024 * 1024;
+
+struct RateWindow {
+  started: Instant,
+  reported: u32,
+  suppressed: u32,
+}
+
+static RATE: Mutex<Option<RateWindow>> = Mutex::new(None);
+
+/// Records an unhandled frontend error in the shell log and `frontend-errors.log`, so crash
+/// snapshots hold both sides. At most 20 reports a minute are kept; the rest are counted.
+#[tauri::command]
+pub fn report_frontend_error(app: AppHandle, message: String, stack: Option<String>) {
+  let Some(suppressed) = admit() else {
+    return;
+  };
+  if suppressed > 0 {
+    log::warn!("[frontend] {suppressed} error reports suppressed by rate limit");
+  }
+  log::error!("[frontend] {message}");
+
+  let mut entry = format!("[{}] {message}\n", chrono::Local::now().to_rfc3339());
+  if let Some(stack) = stack.filter(|stack| !stack.trim().is_empty()) {
+    entry.push_str(&stack);
+    entry.push('\n');
+  }
+  if suppressed > 0 {
+    entry.push_str(&format!("({suppressed} earlier reports suppressed)\n"));
+  }
+  if let Err(err) = append(&app, &entry) {
+    log::warn!("failed to write {}: {err}", logs::FRONTEND_ERRORS_LOG);
+  }
+}
+
+/// `Some(suppressed_before)` when this report is within the limit.
+fn admit() -> Option<u32> {
+  let mut rate = RATE.lock().ok()?;
+  let window = rate.get_or_insert(RateWindow {
+    started: Instant::now(),
+    reported: 0,
+    suppressed: 0,
+  });
+  let mut suppressed = 0;
+  if window.started.elapsed() >= RATE_WINDOW {
+    suppressed = window.suppressed;
+    *window = RateWindow {
+      started: Instant::now(),
+      reported: 0,
+      suppressed: 0,
+    };
+  }
+  if window.reported >= MAX_REPORTS_PER_WINDOW {
+    window.suppressed += 1;
+    return None;
+  }
+  window.reported += 1;
+  Some(suppressed)
+}
+
+fn append(app: &AppHandle, entry: &str) -> std::io::Result<()> {
+  let path = logs::log_dir(&backend::resolve_data_root(app)).join(logs::FRONTEND_ERRORS_LOG);
+  let too_big = std::fs::metadata(&path).is_ok_and(|metadata| metadata.len() > MAX_LOG_BYTES);
+  let mut file = std::fs::OpenOptions::new()
+    .create(true)
+    .append(true)
+    .open(&path)?;
+  if too_big {
+    file.set_len(0)?;
+  }
+  file.write_all(entry.as_bytes())
+}
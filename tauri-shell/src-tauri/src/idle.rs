@@ -0,0 +1,82 @@
+//! Defers a backend restart (to pick up an update or new config) until the frontend has no
+//! operation in flight, so a long-running query isn't cut off.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Condvar, Mutex};
+use std::time::{Duration, Instant};
+
+use tauri::AppHandle;
+
+use crate::{backend, readiness, suspend, tasks};
+
+/// How long a scheduled restart waits for idle before going ahead anyway; a frontend that
+/// crashed mid-operation would otherwise never clear its busy flag.
+const DEFAULT_IDLE_TIMEOUT: Duration = Duration::from_secs(10 * 60);
+/// Upper bound on each wait, so the thread notices shutdown.
+const IDLE_CHECK_INTERVAL: Duration = Duration::from_secs(1);
+
+static BUSY: Mutex<bool> = Mutex::new(false);
+static BUSY_CHANGED: Condvar = Condvar::new();
+static RESTART_SCHEDULED: AtomicBool = AtomicBool::new(false);
+
+/// Set while the frontend has work in flight against the backend, cleared when it is done.
+/// Work starting wakes a backend suspended while hidden; going idle lets it be suspended.
+#[tauri::command]
+pub fn set_frontend_busy(app: AppHandle, busy: bool) {
+  if let Ok(mut current) = BUSY.lock() {
+    *current = busy;
+    BUSY_CHANGED.notify_all();
+  }
+  if busy {
+    suspend::resume(&app);
+  } else {
+    suspend::schedule(&app);
+  }
+}
+
+pub fn is_busy() -> bool {
+  BUSY.lock().is_ok_and(|busy| *busy)
+}
+
+/// Restarts the backend once the frontend isn't busy, or after `timeout_ms` (default ten
+/// minutes) regardless. Returns right away; a restart already scheduled absorbs repeat calls.
+#[tauri::command]
+pub fn schedule_restart_when_idle(app: AppHandle, timeout_ms: Option<u64>) {
+  if RESTART_SCHEDULED.swap(true, Ordering::SeqCst) {
+    return;
+  }
+  let timeout = timeout_ms.map_or(DEFAULT_IDLE_TIMEOUT, Duration::from_millis);
+  log::info!("backend restart scheduled for when the frontend is idle");
+  tasks::spawn("idle-restart", move || {
+    let idle = wait_for_idle(timeout);
+    RESTART_SCHEDULED.store(false, Ordering::SeqCst);
+    if readiness::shutdown_requested() {
+      return;
+    }
+    if !idle {
+      log::warn!("frontend still busy after {timeout:?}; restarting backend anyway");
+    }
+    if let Err(err) = backend::restart(&app) {
+      log::error!("scheduled backend restart failed: {err:#}");
+    }
+  });
+}
+
+/// Whether the frontend went idle before `timeout`.
+fn wait_for_idle(timeout: Duration) -> bool {
+  let deadline = Instant::now() + timeout;
+  let Ok(mut busy) = BUSY.lock() else {
+    return false;
+  };
+  while *busy {
+    let remaining = deadline.saturating_duration_since(Instant::now());
+    if remaining.is_zero() || readiness::shutdown_requested() {
+      return false;
+    }
+    busy = match BUSY_CHANGED.wait_timeout(busy, remaining.min(IDLE_CHECK_INTERVAL)) {
+      Ok((busy, _)) => busy,
+      Err(_) => return false,
+    };
+  }
+  true
+}
@@ -0,0 +1,67 @@
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use serde::Deserialize;
+
+/// Optional list of shipped files (and sizes) written next to the backend binary at build time.
+const MANIFEST_FILE: &str = "manifest.json";
+
+/// Files every PyInstaller onedir build carries alongside the executable.
+const REQUIRED_FILES: &[&str] = &["_internal/base_library.zip"];
+
+/// Launch failure caused by a damaged install rather than a runtime problem; setup shows
+/// it to the user since the backend can never come up.
+#[derive(Debug)]
+pub struct CorruptInstall(pub String);
+
+impl std::fmt::Display for CorruptInstall {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    write!(f, "installation appears corrupted, please reinstall Pluto Duck ({})", self.0)
+  }
+}
+
+impl std::error::Error for CorruptInstall {}
+
+#[derive(Deserialize)]
+struct Manifest {
+  files: Vec<ManifestEntry>,
+}
+
+#[derive(Deserialize)]
+struct ManifestEntry {
+  path: String,
+  size: Option<u64>,
+}
+
+/// Verifies the bundled backend directory is complete before spawning it, so a partial
+/// install fails with a reinstall hint instead of an obscure backend crash.
+pub fn verify_backend_dir(backend_dir: &Path) -> Result<()> {
+  let mut problems: Vec<String> = REQUIRED_FILES
+    .iter()
+    .filter(|relative| !backend_dir.join(relative).exists())
+    .map(|relative| format!("missing {relative}"))
+    .collect();
+
+  let manifest_path = backend_dir.join(MANIFEST_FILE);
+  if manifest_path.exists() {
+    let contents = std::fs::read_to_string(&manifest_path).context("failed to read backend manifest")?;
+    let manifest: Manifest = serde_json::from_str(&contents).context("backend manifest is malformed")?;
+    for entry in manifest.files {
+      let path = backend_dir.join(&entry.path);
+      match (std::fs::metadata(&path), entry.size) {
+        (Err(_), _) => problems.push(format!("missing {}", entry.path)),
+        (Ok(metadata), Some(expected)) if metadata.len() != expected => problems.push(format!(
+          "{} is {} bytes, expected {expected}",
+          entry.path,
+          metadata.len()
+        )),
+        _ => {}
+      }
+    }
+  }
+
+  if !problems.is_empty() {
+    return Err(CorruptInstall(problems.join(", ")).into());
+  }
+  Ok(())
+}
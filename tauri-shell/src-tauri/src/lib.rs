@@ -1,7 +1,73 @@
-use tauri::{Manager, WebviewUrl, WebviewWindowBuilder};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+
+use tauri::{AppHandle, Emitter, Manager, WebviewUrl, WebviewWindow, WebviewWindowBuilder};
+use tauri_plugin_dialog::{DialogExt, MessageDialogKind};
 #[cfg(target_os = "macos")]
 use tauri::TitleBarStyle;
 
+mod antivirus;
+mod api;
+mod appearance;
+mod assets;
+mod autostart;
+mod backend;
+mod backup;
+mod branding;
+mod config;
+mod crash;
+mod deeplink;
+mod developer;
+mod diagnostics;
+mod dialogs;
+mod downloads;
+mod feature_flags;
+mod frontend_errors;
+mod idle;
+mod integrity;
+mod limits;
+mod locale;
+mod logs;
+mod memory;
+mod navigation;
+mod network;
+mod preferences;
+mod preflight;
+mod process;
+mod readiness;
+mod recent;
+mod renderer;
+mod request_headers;
+mod secrets;
+mod setup_script;
+mod shortcut;
+mod snapshot;
+mod startup_trace;
+mod strays;
+mod suspend;
+mod status;
+mod tasks;
+mod titlebar;
+mod tls;
+mod tray;
+mod updater;
+mod user_agent;
+mod volume;
+mod window;
+mod workers;
+
+/// How long the frontend gets to wind down after `shutting-down` before its backend goes away.
+const FRONTEND_SHUTDOWN_GRACE: Duration = Duration::from_millis(300);
+
+static FRONTEND_STOPPED: AtomicBool = AtomicBool::new(false);
+
+/// Files `open_path` refuses: opening these would run them rather than show their contents.
+const EXECUTABLE_EXTENSIONS: &[&str] = &[
+  "app", "bat", "cmd", "com", "command", "cpl", "exe", "hta", "jar", "js", "jse", "lnk", "msc",
+  "msi", "msp", "pif", "pkg", "ps1", "reg", "scr", "sh", "vbe", "vbs", "workflow", "wsf",
+];
+
 #[tauri::command]
 fn open_external_url(url: String) -> Result<(), String> {
   let trimmed = url.trim();
@@ -34,17 +100,321 @@ fn open_external_url(url: String) -> Result<(), String> {
   }
 }
 
+/// Opens a local file (e.g. an exported CSV) in its default application. Only existing,
+/// non-executable files are accepted.
+#[tauri::command]
+fn open_path(path: PathBuf) -> Result<(), String> {
+  // Resolve symlinks so a link can't smuggle an executable past the checks.
+  let target = std::fs::canonicalize(&path)
+    .map_err(|err| format!("Cannot open {}: {err}", path.display()))?;
+  if !target.is_file() {
+    return Err(format!("{} is not a file", path.display()));
+  }
+  if is_executable(&path) || is_executable(&target) {
+    return Err(format!("Refusing to open executable file {}", path.display()));
+  }
+  open_with_default_app(&path)
+}
+
+fn is_executable(path: &Path) -> bool {
+  let by_extension = path
+    .extension()
+    .and_then(|extension| extension.to_str())
+    .is_some_and(|extension| {
+      EXECUTABLE_EXTENSIONS
+        .iter()
+        .any(|blocked| extension.eq_ignore_ascii_case(blocked))
+    });
+  #[cfg(unix)]
+  {
+    use std::os::unix::fs::PermissionsExt;
+    let by_mode = std::fs::metadata(path)
+      .is_ok_and(|metadata| metadata.permissions().mode() & 0o111 != 0);
+    by_extension || by_mode
+  }
+  #[cfg(not(unix))]
+  by_extension
+}
+
+#[cfg(not(windows))]
+fn open_with_default_app(path: &Path) -> Result<(), String> {
+  let opener = if cfg!(target_os = "macos") { "open" } else { "xdg-open" };
+  let status = std::process::Command::new(opener)
+    .arg(path)
+    .status()
+    .map_err(|err| format!("Failed to launch {opener}: {err}"))?;
+  if status.success() {
+    Ok(())
+  } else {
+    Err(format!("{opener} failed with status: {status}"))
+  }
+}
+
+/// ShellExecute rather than `cmd /C start`, which would interpret `&` and friends in the path.
+#[cfg(windows)]
+fn open_with_default_app(path: &Path) -> Result<(), String> {
+  use std::os::windows::ffi::OsStrExt;
+  use windows_sys::Win32::UI::Shell::ShellExecuteW;
+  use windows_sys::Win32::UI::WindowsAndMessaging::SW_SHOWNORMAL;
+
+  let wide = |value: &std::ffi::OsStr| value.encode_wide().chain([0]).collect::<Vec<u16>>();
+  let operation = wide("open".as_ref());
+  let file = wide(path.as_os_str());
+  // SAFETY: both strings are NUL-terminated and outlive the call; the other pointers may be null.
+  let result = unsafe {
+    ShellExecuteW(
+      std::ptr::null_mut(),
+      operation.as_ptr(),
+      file.as_ptr(),
+      std::ptr::null(),
+      std::ptr::null(),
+      SW_SHOWNORMAL,
+    )
+  };
+  // Values up to 32 are error codes.
+  if result as usize > 32 {
+    Ok(())
+  } else {
+    Err(format!("Failed to open {} (error {})", path.display(), result as usize))
+  }
+}
+
+/// Restarts the whole app. The backend is stopped first so the new instance doesn't race the
+/// old process for its port.
+#[tauri::command]
+fn relaunch_app(app: AppHandle) {
+  log::info!("relaunching app");
+  shut_down_in_order(&app);
+  app.restart();
+}
+
+/// Teardown order for exit: the frontend stops making requests, its windows go away, and only
+/// then is the backend stopped, so a dying backend doesn't fill the logs with refused requests.
+fn shut_down_in_order(app: &AppHandle) {
+  stop_frontend(app);
+  backend::shutdown(app);
+}
+
+/// Cancels startup waits, emits `shutting-down`, gives the page a moment to cancel polling,
+/// then hides its windows.
+/// Runs once; by `RunEvent::Exit` the windows are usually gone, so this also runs on
+/// `ExitRequested`.
+fn stop_frontend(app: &AppHandle) {
+  readiness::request_shutdown(app);
+  if FRONTEND_STOPPED.swap(true, Ordering::SeqCst) {
+    return;
+  }
+  let windows = app.webview_windows();
+  if windows.is_empty() {
+    return;
+  }
+  let _ = app.emit("shutting-down", ());
+  std::thread::sleep(FRONTEND_SHUTDOWN_GRACE);
+  for window in windows.values() {
+    let _ = window.hide();
+  }
+}
+
+/// Single route back to a visible main window for the tray, the Dock, deep links and the
+/// global shortcut. Recreates the window if it no longer exists, so there is always a way back.
+pub(crate) fn show_and_focus_main_window(app: &AppHandle) -> Option<WebviewWindow> {
+  let window = match app.get_webview_window("main") {
+    Some(window) => window,
+    None => {
+      log::warn!("main window missing; recreating it");
+      match create_main_window(app) {
+        Ok(window) => window,
+        Err(err) => {
+          log::error!("failed to recreate main window: {err}");
+          return None;
+        }
+      }
+    }
+  };
+  let _ = window.unminimize();
+  let _ = window.show();
+  let _ = window.set_focus();
+  backend::start_if_stopped_while_hidden(app);
+  Some(window)
+}
+
+/// Builds the main window from its `create: false` entry in tauri.conf.json, so the User-Agent
+/// can be composed at runtime, and applies the native chrome and window event handling.
+fn create_main_window(app: &AppHandle) -> tauri::Result<WebviewWindow> {
+  let main_config = app
+    .config()
+    .app
+    .windows
+    .iter()
+    .find(|config| config.label == "main")
+    .cloned();
+  let mut window_builder = match &main_config {
+    Some(config) => WebviewWindowBuilder::from_config(app, config)?,
+    None => WebviewWindowBuilder::new(app, "main", WebviewUrl::default())
+      .title("Pluto Duck")
+      .inner_size(1400.0, 900.0)
+      .resizable(true),
+  };
+  window_builder = window_builder
+    .user_agent(&user_agent::effective_user_agent(app))
+    .initialization_script(feature_flags::init_script(app))
+    .on_download(downloads::handle);
+  #[cfg(windows)]
+  if tls::enabled() {
+    window_builder = window_builder.additional_browser_args(tls::WEBVIEW2_ARGS);
+  }
+
+  #[cfg(target_os = "macos")]
+  {
+    window_builder = window_builder
+      .hidden_title(true)
+      .title_bar_style(TitleBarStyle::Overlay);
+  }
+
+  let window = window_builder.build()?;
+  tls::trust_in_webview(&window);
+  renderer::watch(&window);
+
+  // Apply macOS native titlebar customizations
+  #[cfg(target_os = "macos")]
+  titlebar::apply_titlebar(&window, true);
+
+  branding::restore(&window);
+  appearance::restore_accent_color(&window);
+  window::restore_size_constraints(&window);
+  window::restore_monitor(&window);
+  startup_trace::record("main window", || format!("created at {:?}", window.url()));
+  navigation::navigate_to_frontend(&window);
+  attach_window_events(&window);
+  Ok(window)
+}
+
+/// Close hides instead of quitting unless the frontend changed the close behavior; minimize
+/// can hide to the tray; state changes and throttled move/resize events are pushed to the
+/// frontend.
+fn attach_window_events(window: &WebviewWindow) {
+  let window_clone = window.clone();
+  let move_throttle = window::Throttle::default();
+  let resize_throttle = window::Throttle::default();
+  let label = window.label().to_string();
+  window.on_window_event(move |event| {
+    startup_trace::record("window event", || format!("{label}: {event:?}"));
+  });
+  window.on_window_event(move |event| match event {
+    tauri::WindowEvent::CloseRequested { api, .. } => match window::CloseBehavior::current() {
+      window::CloseBehavior::Hide => {
+        // Hide window instead of closing the app
+        api.prevent_close();
+        let _ = window_clone.hide();
+        backend::stop_while_hidden(window_clone.app_handle());
+      }
+      window::CloseBehavior::Prompt => {
+        api.prevent_close();
+        window::confirm_quit(&window_clone);
+      }
+      window::CloseBehavior::Quit => {
+        api.prevent_close();
+        window_clone.app_handle().exit(0);
+      }
+    },
+    // There is no dedicated minimize event; a resize into the minimized state is the signal.
+    tauri::WindowEvent::Resized(_) => {
+      let minimize_to_tray = window_clone
+        .state::<preferences::Preferences>()
+        .get_bool(preferences::MINIMIZE_TO_TRAY, false);
+      if minimize_to_tray && window_clone.is_minimized().unwrap_or(false) {
+        let _ = window_clone.hide();
+        backend::stop_while_hidden(window_clone.app_handle());
+      }
+      window::emit_state_changed(&window_clone);
+      let window = window_clone.clone();
+      resize_throttle.run(move || window::emit_resized(&window));
+    }
+    tauri::WindowEvent::Moved(_) => {
+      let window = window_clone.clone();
+      move_throttle.run(move || window::emit_moved(&window));
+    }
+    tauri::WindowEvent::Focused(focused) => {
+      window::hide_on_blur(&window_clone, *focused);
+      window::emit_state_changed(&window_clone);
+    }
+    _ => {}
+  });
+}
+
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
-  tauri::Builder::default()
+  readiness::record_process_start();
+  startup_trace::init();
+  feature_flags::parse_cli_args(std::env::args().skip(1));
+  let mut builder = tauri::Builder::default()
     .plugin(tauri_plugin_deep_link::init())
+    .register_uri_scheme_protocol(assets::SCHEME, |_ctx, request| assets::serve(&request))
+    .plugin(autostart::init())
     .plugin(tauri_plugin_dialog::init())
-    .plugin(tauri_plugin_process::init())
-    .plugin(tauri_plugin_updater::Builder::new().build())
+    .plugin(tauri_plugin_process::init());
+  #[cfg(target_os = "macos")]
+  {
+    builder = builder.on_web_content_process_terminate(|webview| {
+      renderer::handle_crash(webview.clone(), "crashed");
+    });
+  }
+  if updater::is_disabled() {
+    log::info!("updater disabled by environment; registering stub plugin");
+    builder = builder.plugin(updater::disabled_plugin());
+  } else {
+    builder = builder.plugin(tauri_plugin_updater::Builder::new().build());
+  }
+
+  builder
+    .plugin(request_headers::init())
+    .plugin(navigation::init())
+    .manage(deeplink::AuthCallbackQueue::default())
+    .manage(diagnostics::VersionsCache::default())
+    .manage(readiness::AppMounted::default())
+    .manage(recent::RecentItems::default())
+    .manage(request_headers::RequestHeaders::default())
+    .manage(status::BackendStatusState::default())
+    .manage(workers::WorkerState::default())
     .setup(|app| {
+      app.manage(preferences::Preferences::load(app.handle()));
+      app.manage(config::EnvConfig::load(app.handle()));
+      startup_trace::record("setup", || "preferences and environment config loaded".into());
       if let Err(err) = backend::launch(app) {
+        startup_trace::record("backend launch failed", || format!("{err:?}"));
         log::error!("backend launch failed: {err:?}");
         eprintln!("backend launch failed: {err:?}");
+        let message = err
+          .downcast_ref::<integrity::CorruptInstall>()
+          .map(ToString::to_string)
+          .or_else(|| {
+            err
+              .downcast_ref::<backend::DataRootNotWritable>()
+              .map(ToString::to_string)
+          })
+          .or_else(|| {
+            err
+              .downcast_ref::<volume::DataVolumeMissing>()
+              .map(ToString::to_string)
+          })
+          .or_else(|| {
+            err
+              .downcast_ref::<antivirus::BlockedByAntivirus>()
+              .map(ToString::to_string)
+          })
+          .or_else(|| {
+            err
+              .downcast_ref::<setup_script::SetupFailed>()
+              .map(ToString::to_string)
+          });
+        if let Some(message) = message {
+          app
+            .dialog()
+            .message(message)
+            .title("Pluto Duck")
+            .kind(MessageDialogKind::Error)
+            .show(|_| {});
+        }
       }
       if cfg!(debug_assertions) {
         app.handle().plugin(
@@ -53,68 +423,117 @@ pub fn run() {
             .build(),
         )?;
       }
+      if !updater::is_disabled() {
+        updater::log_proxy_settings();
+        updater::log_config_problems(app.handle());
+      }
       
-      // Get or create main window
-      let window = if let Some(existing) = app.get_webview_window("main") {
-        existing
-      } else {
-        let mut window_builder = WebviewWindowBuilder::new(app, "main", WebviewUrl::default())
-          .title("Pluto Duck")
-          .inner_size(1400.0, 900.0)
-          .resizable(true);
-
-        #[cfg(target_os = "macos")]
-        {
-          window_builder = window_builder
-            .hidden_title(true)
-            .title_bar_style(TitleBarStyle::Overlay);
-        }
-
-        window_builder.build()?
+      let window = match app.get_webview_window("main") {
+        Some(existing) => existing,
+        None => create_main_window(app.handle())?,
       };
 
-      // Apply macOS native titlebar customizations
-      #[cfg(target_os = "macos")]
-      {
-        use cocoa::appkit::{NSColor, NSWindow, NSWindowTitleVisibility};
-        use cocoa::base::{id, nil, NO, YES};
-
-        if let Ok(ns_window) = window.ns_window() {
-          let ns_window = ns_window as id;
-          unsafe {
-            ns_window.setTitlebarAppearsTransparent_(YES);
-            ns_window.setOpaque_(NO);
-            ns_window.setBackgroundColor_(NSColor::clearColor(nil));
-            ns_window.setTitleVisibility_(NSWindowTitleVisibility::NSWindowTitleHidden);
-          }
-        }
-
-        // Ensure the system knows our desired titlebar height without per-resize tweaking
-        #[allow(unused_must_use)]
-        {
-          apply_titlebar_accessory(&window, 40.0);
-          // apply_unified_toolbar(&window);  // 방법 2: Toolbar 제거로 separator 해결 시도
-        }
+      if autostart::is_autostart_launch() {
+        log::info!("launched at login; starting hidden in the tray");
+        let _ = window.hide();
+      } else if let Some(timeout) = readiness::mount_timeout() {
+        readiness::reveal_when_mounted(window.clone(), timeout);
       }
 
-      // Suppress unused variable warning on non-macOS
-      let _ = &window;
-
-      // Handle window close event (hide instead of quit) for all windows
-      for (_, window) in app.webview_windows() {
-        let window_clone = window.clone();
-        window.on_window_event(move |event| {
-          if let tauri::WindowEvent::CloseRequested { api, .. } = event {
-            // Hide window instead of closing the app
-            api.prevent_close();
-            let _ = window_clone.hide();
-          }
-        });
+      if let Err(err) = tray::install(app) {
+        log::warn!("failed to install tray icon: {err}");
       }
-      
+      recent::install(app);
+      deeplink::verify_registration(app.handle());
+      network::watch(app.handle());
+      locale::watch(app.handle());
+      if let Err(err) = shortcut::install(app) {
+        log::warn!("failed to set up global shortcut: {err}");
+      }
+
       Ok(())
     })
-    .invoke_handler(tauri::generate_handler![open_external_url])
+    .invoke_handler(tauri::generate_handler![
+      open_external_url,
+      open_path,
+      relaunch_app,
+      antivirus::check_file_accessible,
+      appearance::set_accent_color,
+      autostart::get_autostart,
+      autostart::launched_at_login,
+      autostart::set_autostart,
+      backend::backend_port,
+      backend::force_kill_server,
+      backend::pause_server,
+      backend::resume_server,
+      backend::server_root_path,
+      backup::backup_data,
+      backup::checkpoint_database,
+      backup::restore_data,
+      branding::reapply_branding,
+      crash::list_crash_reports,
+      crash::read_crash_report,
+      deeplink::ack_deeplink,
+      deeplink::deeplink_scheme_status,
+      deeplink::pending_auth_callbacks,
+      deeplink::simulate_auth_callback,
+      developer::developer_mode,
+      developer::set_developer_mode,
+      diagnostics::build_info,
+      diagnostics::install_id,
+      diagnostics::startup_duration_ms,
+      diagnostics::system_info,
+      diagnostics::test_connection,
+      diagnostics::versions,
+      dialogs::pick_directory,
+      dialogs::pick_file,
+      dialogs::save_file,
+      downloads::set_download_dir,
+      downloads::stream_export_to_file,
+      frontend_errors::report_frontend_error,
+      idle::schedule_restart_when_idle,
+      idle::set_frontend_busy,
+      logs::clear_server_logs,
+      logs::get_log_paths,
+      logs::log_storage_info,
+      logs::pause_log_forwarding,
+      logs::resume_log_forwarding,
+      memory::trim_backend_memory,
+      navigation::report_navigation_error,
+      navigation::effective_frontend_url,
+      network::network_status,
+      preferences::export_preferences,
+      preferences::get_preferences,
+      preferences::import_preferences,
+      preferences::reload_preferences,
+      preferences::set_preference,
+      preflight::preflight_check,
+      readiness::notify_app_mounted,
+      recent::recent_files,
+      request_headers::get_request_headers,
+      request_headers::set_request_headers,
+      snapshot::state_snapshot,
+      status::backend_status,
+      strays::find_stray_servers,
+      strays::kill_stray_servers,
+      suspend::set_suspend_when_hidden,
+      tasks::list_background_tasks,
+      titlebar::set_titlebar_mode,
+      updater::updates_enabled,
+      updater::updater_config_status,
+      user_agent::get_user_agent,
+      user_agent::set_user_agent,
+      window::clear_webview_cache,
+      window::close_window,
+      window::list_monitors,
+      window::minimize_window,
+      window::move_to_monitor,
+      window::set_close_behavior,
+      window::set_size_constraints,
+      window::set_window_icon,
+      window::toggle_maximize_window,
+      window::window_state,
+    ])
     .build(tauri::generate_context!())
     .expect("error while building tauri application")
     .run(|app_handle, event| {
@@ -126,225 +545,25 @@ pub fn run() {
         tauri::RunEvent::Reopen { has_visible_windows, .. } => {
           log::info!("App reopen event - has_visible_windows: {}", has_visible_windows);
           if !has_visible_windows {
-            // Show all windows when app is activated from Dock
-            for (_, window) in app_handle.webview_windows() {
-              let _ = window.show();
-              let _ = window.set_focus();
-            }
+            // Bring the main window back when the app is activated from the Dock
+            show_and_focus_main_window(app_handle);
           }
         }
+        #[cfg(any(target_os = "macos", target_os = "ios"))]
         tauri::RunEvent::Opened { urls } => {
           if urls.is_empty() {
             return;
           }
           log::info!("App opened with URLs: {:?}", urls);
-          if let Some(window) = app_handle.get_webview_window("main") {
-            let _ = window.show();
-            let _ = window.set_focus();
-            for url in urls {
-              let url_string = url.to_string();
-              if let Ok(serialized) = serde_json::to_string(&url_string) {
-                let script = format!(
-                  "window.__plutoAuthCallbackQueue = window.__plutoAuthCallbackQueue || [];window.__plutoAuthCallbackQueue.push({0});window.dispatchEvent(new CustomEvent('pluto-auth-callback', {{ detail: {{ url: {0} }} }}));",
-                  serialized
-                );
-                let _ = window.eval(&script);
-              }
-            }
-          }
+          let urls: Vec<String> = urls.iter().map(|url| url.to_string()).collect();
+          deeplink::dispatch_auth_callback(app_handle, &urls);
         }
+        tauri::RunEvent::ExitRequested { .. } => stop_frontend(app_handle),
         tauri::RunEvent::Exit => {
           log::info!("App is exiting - cleaning up backend");
-          if let Some(state) = app_handle.try_state::<backend::BackendState>() {
-            if let Ok(mut guard) = state.lock() {
-              if let Some(mut child) = guard.take() {
-                log::info!("Killing backend process on exit...");
-                let _ = child.kill();
-                let _ = child.wait();
-                log::info!("Backend process killed on exit");
-              }
-            }
-          }
+          shut_down_in_order(app_handle);
         }
         _ => {}
       }
     });
 }
-
-#[cfg(target_os = "macos")]
-fn apply_titlebar_accessory(window: &tauri::WebviewWindow, height: f64) {
-  use cocoa::appkit::NSView;
-  use cocoa::base::{id, nil, YES};
-  use cocoa::foundation::{NSPoint, NSRect, NSSize};
-  use objc::{class, msg_send, sel, sel_impl};
-
-  if let Ok(ns_window) = window.ns_window() {
-    let ns_window = ns_window as id;
-    unsafe {
-      let accessory: id = msg_send![class!(NSTitlebarAccessoryViewController), new];
-      let view: id = NSView::alloc(nil).initWithFrame_(NSRect::new(
-        NSPoint::new(0.0, 0.0),
-        NSSize::new(1.0, height),
-      ));
-      let _: () = msg_send![view, setWantsLayer: YES];
-      // Transparent accessory; only height matters for layout
-      let _: () = msg_send![view, setAlphaValue: 0.0f64];
-
-      let _: () = msg_send![accessory, setView: view];
-      // Add accessory so AppKit derives titlebar height from its view
-      let _: () = msg_send![ns_window, addTitlebarAccessoryViewController: accessory];
-    }
-  }
-}
-
-#[cfg(target_os = "macos")]
-fn apply_unified_toolbar(window: &tauri::WebviewWindow) {
-  use cocoa::base::{id, nil, NO, YES, BOOL};
-  use cocoa::foundation::NSString;
-  use objc::{class, msg_send, sel, sel_impl};
-
-  if let Ok(ns_window) = window.ns_window() {
-    let ns_window = ns_window as id;
-    unsafe {
-      // Create NSToolbar with an identifier
-      let identifier = NSString::alloc(nil).init_str("PlutoDuckToolbar");
-      let toolbar: id = msg_send![class!(NSToolbar), alloc];
-      let toolbar: id = msg_send![toolbar, initWithIdentifier: identifier];
-
-      // Optional cosmetic adjustments
-      let _: () = msg_send![toolbar, setShowsBaselineSeparator: NO];
-      // Small size mode (1). Default is 0. This helps lower the baseline.
-      let _: () = msg_send![toolbar, setSizeMode: 1u64];
-
-      // Attach toolbar to window
-      let _: () = msg_send![ns_window, setToolbar: toolbar];
-
-      // Try to center/compact further by setting toolbar style when available.
-      // We avoid hardcoding NSWindowToolbarStyle enums to keep compatibility.
-      // If the selector exists, set to UnifiedCompact (commonly = 5) as a best-effort.
-      let sel_toolbarStyle = sel!(setToolbarStyle:);
-      let responds: BOOL = msg_send![ns_window, respondsToSelector: sel_toolbarStyle];
-      if responds == YES {
-        let unified_compact: u64 = 8; // NSWindowToolbarStyleUnifiedCompact (best-effort)
-        let _: () = msg_send![ns_window, setToolbarStyle: unified_compact];
-      }
-    }
-  }
-}
-
-mod backend {
-  use std::path::PathBuf;
-  use std::process::{Child, Command, Stdio};
-  use std::sync::{Arc, Mutex};
-
-  use anyhow::{Context, Result};
-  use log::{error, info};
-  use tauri::{App, AppHandle, Manager};
-
-  const BACKEND_BINARY_DEBUG: &str = "../../dist/pluto-duck-backend/pluto-duck-backend";
-  const BACKEND_RESOURCE_PATH: &str = "_up_/_up_/dist/pluto-duck-backend/pluto-duck-backend";
-  const BACKEND_PORT: u16 = 8123;
-
-  struct BackendProcess(Arc<Mutex<Option<Child>>>);
-
-  impl Drop for BackendProcess {
-    fn drop(&mut self) {
-      info!("BackendProcess dropping - killing backend");
-      if let Ok(mut guard) = self.0.lock() {
-        if let Some(mut child) = guard.take() {
-          info!("Killing backend process...");
-          let _ = child.kill();
-          let _ = child.wait();
-          info!("Backend process killed");
-        }
-      }
-    }
-  }
-
-  pub type BackendState = Arc<Mutex<Option<Child>>>;
-
-  pub fn launch(app: &mut App) -> Result<()> {
-    let app_handle = app.handle();
-    let binary = backend_binary_path(app)?;
-    let data_root = resolve_data_root(&app_handle);
-
-    info!(
-      "launching backend binary {:?} with data root {:?}",
-      binary,
-      data_root
-    );
-
-    let log_dir = data_root.join("logs");
-    std::fs::create_dir_all(&log_dir).context("failed to create log directory")?;
-    let stdout_log = std::fs::File::create(log_dir.join("backend-stdout.log"))
-      .context("failed to create stdout log")?;
-    let stderr_log = std::fs::File::create(log_dir.join("backend-stderr.log"))
-      .context("failed to create stderr log")?;
-
-    let mut command = Command::new(&binary);
-    if let Some(parent) = binary.parent() {
-      command.current_dir(parent);
-    }
-    command
-      .env("PLUTODUCK_DATA_DIR__ROOT", &data_root)
-      .args([
-        "--port",
-        &BACKEND_PORT.to_string(),
-        "--data-root",
-        data_root.to_string_lossy().as_ref(),
-      ])
-      .stdout(Stdio::from(stdout_log))
-      .stderr(Stdio::from(stderr_log));
-
-    let child = command.spawn().context("failed to spawn backend process")?;
-    let state: BackendState = Arc::new(Mutex::new(Some(child)));
-    let process_wrapper = BackendProcess(state.clone());
-
-    app.manage(state);
-    app.manage(process_wrapper);
-
-    info!(
-      "backend process spawned on http://127.0.0.1:{BACKEND_PORT} with data root {:?}",
-      data_root
-    );
-    info!("backend health will be checked by frontend polling");
-
-    Ok(())
-  }
-
-
-  fn backend_binary_path(app: &App) -> Result<PathBuf> {
-    let path = if cfg!(debug_assertions) {
-      PathBuf::from(env!("CARGO_MANIFEST_DIR"))
-        .join(BACKEND_BINARY_DEBUG)
-    } else {
-      app
-        .path()
-        .resource_dir()
-        .context("resource directory unavailable")?
-        .join(BACKEND_RESOURCE_PATH)
-    };
-    if !path.exists() {
-      anyhow::bail!("backend binary not found at {}", path.display());
-    }
-    Ok(path)
-  }
-
-  fn resolve_data_root(app: &AppHandle) -> PathBuf {
-    let base = if cfg!(debug_assertions) {
-      PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../../.dev-data")
-    } else {
-      app
-        .path()
-        .app_data_dir()
-        .unwrap_or_else(|_| std::env::temp_dir().join("pluto_duck"))
-    };
-    let root = base.join("backend");
-    let logs = root.join("logs");
-    if let Err(err) = std::fs::create_dir_all(&logs) {
-      error!("failed to create backend data directories: {err}");
-    }
-    root
-  }
-
-}
@@ -0,0 +1,247 @@
+//! Optional OS-enforced memory cap for the backend: a cgroup v2 on Linux, a Job Object on
+//! Windows. Off unless `PLUTODUCK_BACKEND_MEMORY_LIMIT_MB` is set.
+
+use std::process::Child;
+
+use anyhow::{Context, Result};
+use log::{info, warn};
+
+const MEMORY_LIMIT_ENV: &str = "PLUTODUCK_BACKEND_MEMORY_LIMIT_MB";
+
+/// The configured cap in MB; unset, empty or zero means unlimited.
+pub fn memory_limit_mb() -> Option<u64> {
+  parse_memory_limit(&std::env::var(MEMORY_LIMIT_ENV).ok()?)
+}
+
+fn parse_memory_limit(value: &str) -> Option<u64> {
+  let value = value.trim();
+  if value.is_empty() {
+    return None;
+  }
+  match value.parse::<u64>() {
+    Ok(0) => None,
+    Ok(limit) => Some(limit),
+    Err(_) => {
+      warn!("ignoring {MEMORY_LIMIT_ENV}={value:?}; expected a whole number of megabytes");
+      None
+    }
+  }
+}
+
+/// Keeps the limit attached to one backend process; dropping it releases the OS object.
+pub struct MemoryLimit {
+  limit_mb: u64,
+  inner: imp::Limit,
+}
+
+impl MemoryLimit {
+  /// Places an already spawned child under a `limit_mb` cap.
+  pub fn apply(child: &Child, limit_mb: u64) -> Result<Self> {
+    let bytes = limit_mb
+      .checked_mul(1024 * 1024)
+      .context("memory limit is too large")?;
+    let inner = imp::Limit::apply(child, bytes)?;
+    Ok(Self { limit_mb, inner })
+  }
+
+  pub fn limit_mb(&self) -> u64 {
+    self.limit_mb
+  }
+
+  /// Whether the process ran into the cap, so its exit was the limit's doing.
+  pub fn was_hit(&self) -> bool {
+    self.inner.was_hit()
+  }
+}
+
+#[cfg(target_os = "linux")]
+mod imp {
+  use std::path::PathBuf;
+  use std::process::Child;
+
+  use anyhow::{Context, Result};
+
+  const CGROUP_ROOT: &str = "/sys/fs/cgroup";
+
+  /// A cgroup next to our own, so it sits in a subtree the user session is allowed to manage.
+  pub struct Limit {
+    dir: PathBuf,
+  }
+
+  impl Limit {
+    pub fn apply(child: &Child, bytes: u64) -> Result<Self> {
+      let own = std::fs::read_to_string("/proc/self/cgroup").context("failed to read own cgroup")?;
+      let own = own
+        .lines()
+        .find_map(|line| line.strip_prefix("0::"))
+        .context("cgroup v2 is not available")?;
+      let own_dir = PathBuf::from(CGROUP_ROOT).join(own.trim_start_matches('/'));
+      let parent = own_dir.parent().context("own cgroup has no parent")?;
+      let dir = parent.join(format!("plutoduck-backend-{}", child.id()));
+
+      std::fs::create_dir_all(&dir)
+        .with_context(|| format!("failed to create cgroup {}", dir.display()))?;
+      let limit = Self { dir };
+      std::fs::write(limit.dir.join("memory.max"), bytes.to_string())
+        .context("failed to set memory.max (is the memory controller delegated?)")?;
+      // Without swap the limit is hit as an OOM kill rather than the backend slowing to a crawl.
+      let _ = std::fs::write(limit.dir.join("memory.swap.max"), "0");
+      std::fs::write(limit.dir.join("cgroup.procs"), child.id().to_string())
+        .context("failed to move the backend into its cgroup")?;
+      Ok(limit)
+    }
+
+    pub fn was_hit(&self) -> bool {
+      let Ok(events) = std::fs::read_to_string(self.dir.join("memory.events")) else {
+        return false;
+      };
+      events
+        .lines()
+        .filter_map(|line| line.strip_prefix("oom_kill "))
+        .any(|count| count.trim().parse::<u64>().is_ok_and(|count| count > 0))
+    }
+  }
+
+  impl Drop for Limit {
+    fn drop(&mut self) {
+      // Only succeeds once the process is gone; a leftover empty cgroup is harmless.
+      let _ = std::fs::remove_dir(&self.dir);
+    }
+  }
+}
+
+#[cfg(windows)]
+mod imp {
+  use std::os::windows::io::AsRawHandle;
+  use std::process::Child;
+
+  use anyhow::{bail, Result};
+  use windows_sys::Win32::Foundation::{CloseHandle, HANDLE};
+  use windows_sys::Win32::System::JobObjects::{
+    AssignProcessToJobObject, CreateJobObjectW, QueryInformationJobObject,
+    SetInformationJobObject, JobObjectExtendedLimitInformation,
+    JOBOBJECT_EXTENDED_LIMIT_INFORMATION, JOB_OBJECT_LIMIT_JOB_MEMORY,
+  };
+
+  /// Windows fails allocations past the job limit instead of killing the process; the
+  /// Python backend then dies on `MemoryError`, and the peak usage tells us why.
+  pub struct Limit {
+    job: HANDLE,
+    bytes: usize,
+  }
+
+  // SAFETY: a job object handle is a kernel handle usable from any thread.
+  unsafe impl Send for Limit {}
+
+  impl Limit {
+    pub fn apply(child: &Child, bytes: u64) -> Result<Self> {
+      let Ok(bytes) = usize::try_from(bytes) else {
+        bail!("memory limit is too large");
+      };
+      // SAFETY: null attributes and name create an anonymous job with default security.
+      let job = unsafe { CreateJobObjectW(std::ptr::null(), std::ptr::null()) };
+      if job.is_null() {
+        bail!("failed to create job object: {}", std::io::Error::last_os_error());
+      }
+      let limit = Self { job, bytes };
+
+      // SAFETY: the struct is plain data for which all-zeroes is a valid value.
+      let mut info: JOBOBJECT_EXTENDED_LIMIT_INFORMATION = unsafe { std::mem::zeroed() };
+      info.BasicLimitInformation.LimitFlags = JOB_OBJECT_LIMIT_JOB_MEMORY;
+      info.JobMemoryLimit = bytes;
+      // SAFETY: `info` lives for the call and the size matches the information class.
+      let ok = unsafe {
+        SetInformationJobObject(
+          limit.job,
+          JobObjectExtendedLimitInformation,
+          std::ptr::from_ref(&info).cast(),
+          std::mem::size_of::<JOBOBJECT_EXTENDED_LIMIT_INFORMATION>() as u32,
+        )
+      };
+      if ok == 0 {
+        bail!("failed to set job memory limit: {}", std::io::Error::last_os_error());
+      }
+      // SAFETY: the child's handle stays open for as long as `child` is alive.
+      if unsafe { AssignProcessToJobObject(limit.job, child.as_raw_handle() as HANDLE) } == 0 {
+        bail!("failed to assign the backend to its job: {}", std::io::Error::last_os_error());
+      }
+      Ok(limit)
+    }
+
+    pub fn was_hit(&self) -> bool {
+      // SAFETY: as above; a zeroed struct is valid and receives the query result.
+      let mut info: JOBOBJECT_EXTENDED_LIMIT_INFORMATION = unsafe { std::mem::zeroed() };
+      let ok = unsafe {
+        QueryInformationJobObject(
+          self.job,
+          JobObjectExtendedLimitInformation,
+          std::ptr::from_mut(&mut info).cast(),
+          std::mem::size_of::<JOBOBJECT_EXTENDED_LIMIT_INFORMATION>() as u32,
+          std::ptr::null_mut(),
+        )
+      };
+      ok != 0 && info.PeakJobMemoryUsed >= self.bytes
+    }
+  }
+
+  impl Drop for Limit {
+    fn drop(&mut self) {
+      // SAFETY: the handle came from CreateJobObjectW and is closed exactly once.
+      unsafe { CloseHandle(self.job) };
+    }
+  }
+}
+
+#[cfg(not(any(target_os = "linux", windows)))]
+mod imp {
+  use std::process::Child;
+
+  use anyhow::{bail, Result};
+
+  pub struct Limit;
+
+  impl Limit {
+    pub fn apply(_child: &Child, _bytes: u64) -> Result<Self> {
+      bail!("backend memory limits are only supported on Linux and Windows")
+    }
+
+    pub fn was_hit(&self) -> bool {
+      false
+    }
+  }
+}
+
+/// Applies the configured limit, if any, warning instead of failing the launch when the OS
+/// refuses it.
+pub fn apply_configured(child: &Child) -> Option<MemoryLimit> {
+  let limit_mb = memory_limit_mb()?;
+  match MemoryLimit::apply(child, limit_mb) {
+    Ok(limit) => {
+      info!("backend memory limited to {limit_mb} MB");
+      Some(limit)
+    }
+    Err(err) => {
+      warn!("{MEMORY_LIMIT_ENV} is set but the limit could not be applied: {err:#}");
+      None
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn empty_or_zero_limits_mean_unlimited() {
+    assert_eq!(parse_memory_limit(""), None);
+    assert_eq!(parse_memory_limit("  "), None);
+    assert_eq!(parse_memory_limit("0"), None);
+  }
+
+  #[test]
+  fn parses_whole_megabytes() {
+    assert_eq!(parse_memory_limit(" 512 "), Some(512));
+    assert_eq!(parse_memory_limit("1.5"), None);
+    assert_eq!(parse_memory_limit("lots"), None);
+  }
+}
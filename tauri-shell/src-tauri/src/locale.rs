@@ -0,0 +1,114 @@
+//! The OS locale and time zone, handed to the backend so the dates it formats match the rest
+//! of the desktop. A packaged app launched from the Dock or Start menu usually has neither
+//! `LANG` nor `TZ` set, so Python would otherwise fall back to `C` and UTC.
+
+use std::process::Command;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use serde::Serialize;
+use tauri::{AppHandle, Emitter};
+
+use crate::{readiness, tasks};
+
+pub const LOCALE_ENV: &str = "PLUTODUCK_LOCALE";
+pub const TIMEZONE_ENV: &str = "PLUTODUCK_TIMEZONE";
+const WATCH_INTERVAL: Duration = Duration::from_secs(60);
+
+/// What the backend was last started with, or the watch last reported.
+static LAST_SEEN: Mutex<Option<LocaleInfo>> = Mutex::new(None);
+
+#[derive(Clone, PartialEq, Serialize)]
+pub struct LocaleInfo {
+  /// BCP 47, e.g. `en-US`.
+  pub locale: Option<String>,
+  /// IANA name, e.g. `Europe/Berlin`.
+  pub timezone: Option<String>,
+}
+
+pub fn detect() -> LocaleInfo {
+  LocaleInfo {
+    locale: sys_locale::get_locale(),
+    timezone: iana_time_zone::get_timezone()
+      .map_err(|err| log::warn!("could not read the OS time zone: {err}"))
+      .ok(),
+  }
+}
+
+/// `en-US` as a POSIX locale name, `en_US.UTF-8`. A bare language has no portable POSIX
+/// name, so it gets none.
+fn posix_locale(locale: &str) -> Option<String> {
+  let mut parts = locale.split(['-', '_']);
+  let language = parts.next()?;
+  let region = parts.find(|part| part.len() == 2)?;
+  Some(format!("{language}_{}.UTF-8", region.to_ascii_uppercase()))
+}
+
+/// Sets `PLUTODUCK_LOCALE` and `PLUTODUCK_TIMEZONE` for the backend, plus `LANG` and `TZ` on
+/// Unix where the shell's own environment doesn't already have them. Called before the env
+/// config's `backendEnv` is applied, so that still wins.
+pub fn apply_to(command: &mut Command) {
+  let info = detect();
+  if let Some(locale) = &info.locale {
+    command.env(LOCALE_ENV, locale);
+    if cfg!(unix) && std::env::var_os("LANG").is_none() {
+      if let Some(lang) = posix_locale(locale) {
+        command.env("LANG", lang);
+      }
+    }
+  }
+  if let Some(timezone) = &info.timezone {
+    command.env(TIMEZONE_ENV, timezone);
+    // The Windows C runtime reads `TZ` as a POSIX rule, not an IANA name, so it's left alone.
+    if cfg!(unix) && std::env::var_os("TZ").is_none() {
+      command.env("TZ", timezone);
+    }
+  }
+  log::info!("backend locale {:?}, time zone {:?}", info.locale, info.timezone);
+  if let Ok(mut last) = LAST_SEEN.lock() {
+    *last = Some(info);
+  }
+}
+
+/// Checks the OS locale and time zone every minute and emits `locale-changed` when either
+/// changed. The running backend keeps its environment; it picks up the new values when it
+/// next restarts. On Linux the locale comes from the environment, so only the time zone can
+/// change underneath us.
+pub fn watch(app: &AppHandle) {
+  let app = app.clone();
+  tasks::spawn("locale-watch", move || {
+    while !readiness::shutdown_requested() {
+      std::thread::sleep(WATCH_INTERVAL);
+      let current = detect();
+      let Ok(mut last) = LAST_SEEN.lock() else {
+        return;
+      };
+      if last.as_ref().is_some_and(|last| *last == current) {
+        continue;
+      }
+      if last.is_some() {
+        log::info!("OS locale is now {:?}, time zone {:?}", current.locale, current.timezone);
+        let _ = app.emit("locale-changed", current.clone());
+      }
+      *last = Some(current);
+    }
+  });
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn maps_bcp47_tags_to_posix_locales() {
+    assert_eq!(posix_locale("en-US").as_deref(), Some("en_US.UTF-8"));
+    assert_eq!(posix_locale("de_de").as_deref(), Some("de_DE.UTF-8"));
+    assert_eq!(posix_locale("zh-Hans-CN").as_deref(), Some("zh_CN.UTF-8"));
+  }
+
+  #[test]
+  fn bare_languages_have_no_posix_locale() {
+    assert_eq!(posix_locale("fr"), None);
+    assert_eq!(posix_locale("zh-Hans"), None);
+  }
+}
@@ -0,0 +1,318 @@
+use std::fs::{File, OpenOptions};
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::preferences::{self, Preferences};
+use crate::{backend, crash, tasks};
+
+pub const LOG_DIR: &str = "logs";
+pub const STDOUT_LOG: &str = "backend-stdout.log";
+pub const STDERR_LOG: &str = "backend-stderr.log";
+pub const FRONTEND_ERRORS_LOG: &str = "frontend-errors.log";
+pub const SETUP_LOG: &str = "setup.log";
+pub const LOG_FILES: [&str; 4] = [STDOUT_LOG, STDERR_LOG, FRONTEND_ERRORS_LOG, SETUP_LOG];
+/// With `append_logs`, a log past this size is moved to `<name>.1` (replacing the previous
+/// one) before the next session appends to it.
+const MAX_APPENDED_LOG_BYTES: u64 = 10 * 1024 * 1024;
+const SESSION_SEPARATOR: &str = "=== session start ";
+/// How far back from the end the forwarder looks for the current session's separator.
+const SEPARATOR_SEARCH_BYTES: u64 = 1024 * 1024;
+const FORWARD_INTERVAL: Duration = Duration::from_millis(250);
+const MAX_BATCH_LINES: usize = 500;
+/// Per forwarding tick, so a crash loop printing tracebacks can't flood the webview either.
+const MAX_ERROR_EVENTS: usize = 20;
+/// Substrings that mark a plain-text line as an error: Python logging's level names and the
+/// first line of an uncaught exception.
+const ERROR_MARKERS: [&str; 3] = ["ERROR", "CRITICAL", "Traceback (most recent call last)"];
+/// `level` values that mark a JSON log line as an error, compared case-insensitively.
+const ERROR_LEVELS: [&str; 3] = ["error", "critical", "fatal"];
+/// Keys structured loggers put the level under.
+const LEVEL_KEYS: [&str; 2] = ["level", "levelname"];
+
+static FORWARDING_PAUSED: AtomicBool = AtomicBool::new(false);
+
+#[derive(Clone, Serialize)]
+struct LogLine {
+  stream: &'static str,
+  line: String,
+}
+
+#[derive(Clone, Serialize)]
+struct LogBatch {
+  lines: Vec<LogLine>,
+  /// Lines beyond the per-batch cap that were skipped.
+  dropped: usize,
+}
+
+/// Whether a backend log line reports an error. JSON lines are judged by their level alone,
+/// so an info message that merely mentions "ERROR" doesn't count.
+fn is_error_line(line: &str) -> bool {
+  let trimmed = line.trim_start();
+  if trimmed.starts_with('{') {
+    if let Ok(serde_json::Value::Object(fields)) = serde_json::from_str(trimmed) {
+      return LEVEL_KEYS
+        .iter()
+        .filter_map(|key| fields.get(*key)?.as_str())
+        .any(|level| ERROR_LEVELS.iter().any(|error| level.eq_ignore_ascii_case(error)));
+    }
+  }
+  ERROR_MARKERS.iter().any(|marker| line.contains(marker))
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LogFileInfo {
+  pub name: String,
+  pub bytes: u64,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LogStorageInfo {
+  pub log_dir: PathBuf,
+  /// Everything in the log directory, largest first: backend, worker and frontend logs.
+  pub files: Vec<LogFileInfo>,
+  /// Logs preserved from sessions that ended abnormally, see `crash`.
+  pub crash_snapshot_bytes: u64,
+  /// Reports written with the `crash_reports` preference on, see `crash`.
+  pub crash_report_bytes: u64,
+  pub total_bytes: u64,
+}
+
+struct TailedLog {
+  stream: &'static str,
+  path: PathBuf,
+  offset: u64,
+  partial: String,
+}
+
+impl TailedLog {
+  fn read_new_lines(&mut self) -> Vec<String> {
+    let Ok(mut file) = File::open(&self.path) else {
+      return Vec::new();
+    };
+    let len = file.metadata().map(|metadata| metadata.len()).unwrap_or(0);
+    // Truncated by a backend restart or `clear_server_logs`.
+    if len < self.offset {
+      self.offset = 0;
+      self.partial.clear();
+    }
+    if len == self.offset || file.seek(SeekFrom::Start(self.offset)).is_err() {
+      return Vec::new();
+    }
+    let mut chunk = Vec::new();
+    if file.take(len - self.offset).read_to_end(&mut chunk).is_err() {
+      return Vec::new();
+    }
+    self.offset += chunk.len() as u64;
+    self.partial.push_str(&String::from_utf8_lossy(&chunk));
+    let mut lines: Vec<String> = self
+      .partial
+      .split('\n')
+      .map(|line| line.trim_end_matches('\r').to_string())
+      .collect();
+    self.partial = lines.pop().unwrap_or_default();
+    lines
+  }
+}
+
+pub fn log_dir(data_root: &Path) -> PathBuf {
+  data_root.join(LOG_DIR)
+}
+
+pub fn log_paths(data_root: &Path) -> Vec<PathBuf> {
+  let dir = log_dir(data_root);
+  LOG_FILES.iter().map(|name| dir.join(name)).collect()
+}
+
+/// Whether the user chose to keep logs across launches, see [`open_session_log`].
+pub fn append_logs(app: &AppHandle) -> bool {
+  app
+    .state::<Preferences>()
+    .get_bool(preferences::APPEND_LOGS, false)
+}
+
+/// Opens `path` in append mode for the child. With O_APPEND the backend keeps writing at the
+/// end of file even after `clear_server_logs` truncates it underneath. The file is truncated
+/// first, unless `append` keeps the earlier sessions: then an oversized file is rotated and a
+/// `=== session start <timestamp> ===` line marks where this session begins.
+pub fn open_session_log(path: &Path, append: bool) -> std::io::Result<File> {
+  if !append {
+    File::create(path)?;
+    return OpenOptions::new().append(true).open(path);
+  }
+  let size = std::fs::metadata(path).map(|metadata| metadata.len()).unwrap_or(0);
+  if size > MAX_APPENDED_LOG_BYTES {
+    std::fs::rename(path, rotated_path(path))?;
+  }
+  let mut file = OpenOptions::new().append(true).create(true).open(path)?;
+  writeln!(file, "{SESSION_SEPARATOR}{} ===", chrono::Local::now().to_rfc3339())?;
+  Ok(file)
+}
+
+fn rotated_path(path: &Path) -> PathBuf {
+  let mut rotated = path.as_os_str().to_owned();
+  rotated.push(".1");
+  PathBuf::from(rotated)
+}
+
+/// Where the current session begins in an appended log, so the forwarder doesn't replay
+/// earlier sessions to the frontend: the last separator, else the end of the file.
+fn session_start_offset(path: &Path) -> u64 {
+  let Ok(mut file) = File::open(path) else {
+    return 0;
+  };
+  let len = file.metadata().map(|metadata| metadata.len()).unwrap_or(0);
+  let start = len.saturating_sub(SEPARATOR_SEARCH_BYTES);
+  let mut tail = Vec::new();
+  if file.seek(SeekFrom::Start(start)).is_err() || file.read_to_end(&mut tail).is_err() {
+    return len;
+  }
+  let separator = SESSION_SEPARATOR.as_bytes();
+  tail
+    .windows(separator.len())
+    .rposition(|window| window == separator)
+    .map_or(len, |position| start + position as u64)
+}
+
+/// Tails the backend logs and forwards new lines as `backend-log-batch` events, coalesced every
+/// 250ms and capped per batch so a chatty backend can't flood the webview. While forwarding is
+/// paused lines are skipped rather than buffered. Error lines are also emitted one by one as
+/// `backend-error`, paused or not, so the UI can surface them without reading the stream.
+pub fn forward_to_frontend(app: AppHandle, data_root: &Path) {
+  let dir = log_dir(data_root);
+  let append = append_logs(&app);
+  let mut tails: Vec<TailedLog> = [("stdout", STDOUT_LOG), ("stderr", STDERR_LOG)]
+    .into_iter()
+    .map(|(stream, name)| {
+      let path = dir.join(name);
+      let offset = if append { session_start_offset(&path) } else { 0 };
+      TailedLog {
+        stream,
+        path,
+        offset,
+        partial: String::new(),
+      }
+    })
+    .collect();
+  tasks::spawn("log-forwarder", move || loop {
+    std::thread::sleep(FORWARD_INTERVAL);
+    let paused = FORWARDING_PAUSED.load(Ordering::SeqCst);
+    let mut batch = LogBatch {
+      lines: Vec::new(),
+      dropped: 0,
+    };
+    let mut errors = 0;
+    for tail in &mut tails {
+      for line in tail.read_new_lines() {
+        if errors < MAX_ERROR_EVENTS && is_error_line(&line) {
+          errors += 1;
+          let error = LogLine {
+            stream: tail.stream,
+            line: line.clone(),
+          };
+          let _ = app.emit("backend-error", error);
+        }
+        if batch.lines.len() < MAX_BATCH_LINES {
+          batch.lines.push(LogLine {
+            stream: tail.stream,
+            line,
+          });
+        } else {
+          batch.dropped += 1;
+        }
+      }
+    }
+    if !paused && !batch.lines.is_empty() {
+      let _ = app.emit("backend-log-batch", batch);
+    }
+  });
+}
+
+#[tauri::command]
+pub fn pause_log_forwarding() {
+  FORWARDING_PAUSED.store(true, Ordering::SeqCst);
+}
+
+#[tauri::command]
+pub fn resume_log_forwarding() {
+  FORWARDING_PAUSED.store(false, Ordering::SeqCst);
+}
+
+#[tauri::command]
+pub fn get_log_paths(app: AppHandle) -> Vec<String> {
+  log_paths(&backend::resolve_data_root(&app))
+    .into_iter()
+    .map(|path| path.to_string_lossy().into_owned())
+    .collect()
+}
+
+/// What the logs take up on disk, for a storage screen offering cleanup.
+#[tauri::command]
+pub fn log_storage_info(app: AppHandle) -> LogStorageInfo {
+  let data_root = backend::resolve_data_root(&app);
+  let log_dir = log_dir(&data_root);
+  let mut files: Vec<LogFileInfo> = std::fs::read_dir(&log_dir)
+    .into_iter()
+    .flatten()
+    .filter_map(|entry| {
+      let entry = entry.ok()?;
+      let metadata = entry.metadata().ok()?;
+      metadata.is_file().then(|| LogFileInfo {
+        name: entry.file_name().to_string_lossy().into_owned(),
+        bytes: metadata.len(),
+      })
+    })
+    .collect();
+  files.sort_by_key(|file| std::cmp::Reverse(file.bytes));
+  let crash_snapshot_bytes = dir_size(&crash::crashes_dir(&data_root));
+  let crash_report_bytes = dir_size(&crash::reports_dir(&data_root));
+  let total_bytes =
+    files.iter().map(|file| file.bytes).sum::<u64>() + crash_snapshot_bytes + crash_report_bytes;
+  LogStorageInfo {
+    log_dir,
+    files,
+    crash_snapshot_bytes,
+    crash_report_bytes,
+    total_bytes,
+  }
+}
+
+fn dir_size(dir: &Path) -> u64 {
+  let Ok(entries) = std::fs::read_dir(dir) else {
+    return 0;
+  };
+  entries
+    .filter_map(Result::ok)
+    .map(|entry| match entry.file_type() {
+      Ok(file_type) if file_type.is_dir() => dir_size(&entry.path()),
+      Ok(file_type) if file_type.is_file() => {
+        entry.metadata().map(|metadata| metadata.len()).unwrap_or(0)
+      }
+      _ => 0,
+    })
+    .sum()
+}
+
+#[tauri::command]
+pub fn clear_server_logs(app: AppHandle) -> Result<(), String> {
+  for path in log_paths(&backend::resolve_data_root(&app)) {
+    let _ = std::fs::remove_file(rotated_path(&path));
+    if !path.exists() {
+      continue;
+    }
+    OpenOptions::new()
+      .write(true)
+      .open(&path)
+      .and_then(|file| file.set_len(0))
+      .map_err(|err| format!("Failed to clear {}: {err}", path.display()))?;
+  }
+  log::info!("backend logs cleared");
+  let _ = app.emit("logs-cleared", ());
+  Ok(())
+}
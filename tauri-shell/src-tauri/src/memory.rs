@@ -0,0 +1,31 @@
+use std::time::Duration;
+
+use serde::Serialize;
+use tauri::State;
+
+use crate::{api, backend, process};
+
+const TRIM_MEMORY_PATH: &str = "/api/v1/system/trim-memory";
+const TRIM_MEMORY_TIMEOUT: Duration = Duration::from_secs(30);
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MemoryTrim {
+  pub before_rss_bytes: Option<u64>,
+  pub after_rss_bytes: Option<u64>,
+}
+
+/// Asks the backend to run a full `gc.collect()` and release freed heap back to the OS, and
+/// reports the process RSS either side so the effect is visible.
+#[tauri::command]
+pub async fn trim_backend_memory(state: State<'_, backend::BackendState>) -> Result<MemoryTrim, String> {
+  let pid = backend::running_pid(&state)?;
+  let before_rss_bytes = process::rss_bytes(pid);
+  api::post(TRIM_MEMORY_PATH, TRIM_MEMORY_TIMEOUT).await?;
+  let after_rss_bytes = process::rss_bytes(pid);
+  log::info!("backend memory trimmed: rss {before_rss_bytes:?} -> {after_rss_bytes:?} bytes");
+  Ok(MemoryTrim {
+    before_rss_bytes,
+    after_rss_bytes,
+  })
+}
@@ -0,0 +1,180 @@
+//! Tells blocked loads apart from a backend that is down. CSP violations are reported by an
+//! init script; failed connections are only visible on Windows, where WebView2 swaps in its
+//! own error page. WebKit does not expose either reason.
+
+use serde::Serialize;
+use tauri::plugin::TauriPlugin;
+use tauri::webview::PageLoadEvent;
+use tauri::{AppHandle, Emitter, Manager, Runtime, Url, WebviewWindow};
+use tauri_plugin_dialog::{DialogExt, MessageDialogKind};
+
+use crate::readiness::AppMounted;
+use crate::config::{self, EnvConfig};
+use crate::{assets, backend, startup_trace};
+
+const INIT_SCRIPT: &str = include_str!("scripts/navigation_errors.js");
+const WEBVIEW2_ERROR_SCHEME: &str = "chrome-error";
+
+#[derive(Clone, Serialize)]
+pub struct NavigationError {
+  /// `csp` or `connection`.
+  pub kind: &'static str,
+  pub url: String,
+  pub message: String,
+}
+
+pub fn init<R: Runtime>() -> TauriPlugin<R> {
+  tauri::plugin::Builder::new("navigation-errors")
+    .js_init_script(INIT_SCRIPT.to_string())
+    .on_page_load(|webview, payload| {
+      startup_trace::record("page load", || {
+        format!("{}: {:?} {}", webview.label(), payload.event(), payload.url())
+      });
+      if payload.event() == PageLoadEvent::Finished
+        && payload.url().scheme() == WEBVIEW2_ERROR_SCHEME
+      {
+        let app = webview.app_handle();
+        let url = frontend_url(app)
+          .map(|url| url.to_string())
+          .unwrap_or_else(backend::origin);
+        let message =
+          format!("Could not connect to {url}. Check that the Pluto Duck server is running.");
+        // Our own page instead of WebView2's, with a retry; it needs nothing from the backend.
+        let mut error_page = assets::url(assets::CONNECTION_ERROR_PAGE);
+        error_page.query_pairs_mut().append_pair("url", &url);
+        if let Err(err) = webview.navigate(error_page) {
+          log::warn!("failed to show the connection error page: {err}");
+        }
+        report(app, webview.label(), NavigationError { kind: "connection", url, message });
+      }
+    })
+    .build()
+}
+
+/// The configured `frontendUrl`, following the backend to the port it bound. Every window
+/// resolves it here so they all load the same frontend from the one shared backend.
+pub fn frontend_url<R: Runtime>(app: &AppHandle<R>) -> Option<Url> {
+  let mut url = app.state::<EnvConfig>().frontend_url()?;
+  backend::follow_active_port(&mut url);
+  Some(url)
+}
+
+/// Where [`navigate_to_frontend`] points a new window, in the same order: `frontendUrl` with
+/// the backend's bound port and scheme, the dev server in debug builds, else the bundled
+/// frontend's own origin.
+fn effective_url(app: &AppHandle) -> Url {
+  if let Some(url) = frontend_url(app) {
+    return url;
+  }
+  if cfg!(debug_assertions) {
+    if let Some((url, _)) = config::dev_url(app) {
+      return url;
+    }
+  }
+  // WebView2 only allows custom schemes through `http://<scheme>.localhost`, as in `assets`.
+  let bundled = if cfg!(windows) {
+    "http://tauri.localhost/"
+  } else {
+    "tauri://localhost/"
+  };
+  Url::parse(bundled).expect("bundled frontend URL is valid")
+}
+
+/// The URL a window loads the frontend from right now, resolved the way new windows resolve it.
+#[tauri::command]
+pub fn effective_frontend_url(app: AppHandle) -> String {
+  effective_url(&app).to_string()
+}
+
+/// Loads the frontend into a new window: `frontendUrl` if configured, else the dev server in
+/// debug builds, else the bundled frontend the window already has. Windows opened while the
+/// backend is stopped for being hidden start it again.
+pub fn navigate_to_frontend(window: &WebviewWindow) {
+  let app = window.app_handle();
+  backend::start_if_stopped_while_hidden(app);
+  if let Some(url) = frontend_url(app) {
+    startup_trace::record("navigate", || format!("frontendUrl {url}"));
+    log::info!("loading frontend from {url} in window {}", window.label());
+    if let Err(err) = window.navigate(url) {
+      log::warn!("failed to load configured frontend URL: {err}");
+    }
+  } else if cfg!(debug_assertions) {
+    navigate_to_dev_url(app, window);
+  }
+}
+
+/// Loads the dev server explicitly instead of relying on the window's implicit `devUrl`, so
+/// a frontend on a non-standard port only needs `PLUTODUCK_DEV_URL` or the env config.
+fn navigate_to_dev_url(app: &AppHandle, window: &WebviewWindow) {
+  let Some((url, source)) = config::dev_url(app) else {
+    log::warn!("no dev URL configured; the window keeps its default content");
+    return;
+  };
+  startup_trace::record("navigate", || format!("dev URL {url} (from {source})"));
+  log::info!("loading dev frontend from {url} (from {source})");
+  if app.config().build.dev_url.as_ref() == Some(&url) {
+    return;
+  }
+  if let Err(err) = window.navigate(url) {
+    log::warn!("failed to load dev URL: {err}");
+  }
+}
+
+/// Called once a restarted backend is ready. Windows showing a frontend served by the backend
+/// (`frontendUrl`) reload it, from the error page if the restart left them on one, then
+/// `backend-reconnected` tells the frontend to clear its connection errors. The bundled
+/// frontend never lost its page and only gets the event.
+pub fn reconnect(app: &AppHandle) {
+  if let Some(frontend_url) = frontend_url(app) {
+    for (label, window) in app.webview_windows() {
+      let Ok(current) = window.url() else {
+        continue;
+      };
+      let target = if current.scheme() == WEBVIEW2_ERROR_SCHEME || assets::is_asset_url(&current) {
+        frontend_url.clone()
+      } else if current.origin() == frontend_url.origin() {
+        current
+      } else {
+        continue;
+      };
+      log::info!("backend restarted; reloading {target} in window {label}");
+      if let Err(err) = window.navigate(target) {
+        log::warn!("failed to reload window {label} after backend restart: {err}");
+      }
+    }
+  }
+  let _ = app.emit("backend-reconnected", ());
+}
+
+/// Logs the error and forwards it to the window as `navigation-error`. A failed connection
+/// leaves the window without a frontend, so if none ever mounted to listen, a native dialog
+/// says what happened instead. CSP reports are only logged: a page can trigger any number
+/// of them and most block nothing the user would notice.
+fn report<R: Runtime>(app: &AppHandle<R>, label: &str, error: NavigationError) {
+  let fatal = error.kind == "connection";
+  if fatal {
+    log::error!("navigation error ({}) for {}: {}", error.kind, error.url, error.message);
+  } else {
+    log::warn!("navigation error ({}) for {}: {}", error.kind, error.url, error.message);
+  }
+  let mounted = app.state::<AppMounted>().is_mounted();
+  let _ = app.emit_to(label, "navigation-error", error.clone());
+  if fatal && !mounted {
+    app
+      .dialog()
+      .message(error.message)
+      .title("Pluto Duck")
+      .kind(MessageDialogKind::Error)
+      .show(|_| {});
+  }
+}
+
+#[tauri::command]
+pub fn report_navigation_error(window: WebviewWindow, kind: String, url: String, detail: String) {
+  // Only CSP reports come from the page; anything else is not ours to trust.
+  if kind != "csp" {
+    return;
+  }
+  let message = format!("The page's Content-Security-Policy ({detail}) blocked {url}.");
+  report(window.app_handle(), window.label(), NavigationError { kind: "csp", url, message });
+}
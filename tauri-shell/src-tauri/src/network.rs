@@ -0,0 +1,116 @@
+//! Internet reachability as the shell sees it. `navigator.onLine` in the webview only reflects
+//! whether some interface is up, so the offline banner relies on this instead.
+
+use std::net::{TcpStream, ToSocketAddrs};
+use std::sync::atomic::{AtomicU8, Ordering};
+use std::time::Duration;
+
+use serde::Serialize;
+use tauri::{AppHandle, Emitter};
+
+use crate::{readiness, tasks};
+
+/// Resolved and connected to as the reachability check; the updater's host, which the app
+/// needs anyway.
+const PROBE_HOST: &str = "fluxloop-ai.github.io";
+const PROBE_PORT: u16 = 443;
+const PROBE_TIMEOUT: Duration = Duration::from_secs(3);
+const WATCH_INTERVAL: Duration = Duration::from_secs(15);
+
+const UNKNOWN: u8 = 0;
+const ONLINE: u8 = 1;
+const OFFLINE: u8 = 2;
+
+/// Last state seen by the watch, so `network-changed` only fires on transitions.
+static LAST_STATE: AtomicU8 = AtomicU8::new(UNKNOWN);
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NetworkStatus {
+  pub online: bool,
+  /// Set when offline: which step of the check failed.
+  pub reason: Option<String>,
+  pub interfaces: Vec<NetworkInterface>,
+}
+
+#[derive(Clone, Serialize)]
+pub struct NetworkInterface {
+  pub name: String,
+  pub addresses: Vec<String>,
+}
+
+#[tauri::command]
+pub async fn network_status() -> Result<NetworkStatus, String> {
+  tauri::async_runtime::spawn_blocking(check)
+    .await
+    .map_err(|err| err.to_string())
+}
+
+/// Polls connectivity in the background and emits `network-changed` with the new status
+/// whenever it flips between online and offline.
+pub fn watch(app: &AppHandle) {
+  let app = app.clone();
+  tasks::spawn("network-watch", move || {
+    while !readiness::shutdown_requested() {
+      let status = check();
+      let state = if status.online { ONLINE } else { OFFLINE };
+      let previous = LAST_STATE.swap(state, Ordering::SeqCst);
+      if previous != state {
+        if previous != UNKNOWN {
+          log::info!("network is now {}", if status.online { "online" } else { "offline" });
+        }
+        let _ = app.emit("network-changed", status);
+      }
+      std::thread::sleep(WATCH_INTERVAL);
+    }
+  });
+}
+
+/// DNS lookup and a TCP connect to [`PROBE_HOST`]. Direct, so a network that only allows
+/// traffic through a proxy reads as offline.
+fn check() -> NetworkStatus {
+  let reason = match (PROBE_HOST, PROBE_PORT).to_socket_addrs() {
+    Err(err) => Some(format!("DNS lookup for {PROBE_HOST} failed: {err}")),
+    Ok(addresses) => {
+      let addresses: Vec<_> = addresses.collect();
+      let connected = addresses
+        .iter()
+        .any(|address| TcpStream::connect_timeout(address, PROBE_TIMEOUT).is_ok());
+      if connected {
+        None
+      } else if addresses.is_empty() {
+        Some(format!("{PROBE_HOST} did not resolve to any address"))
+      } else {
+        Some(format!("could not connect to {PROBE_HOST}:{PROBE_PORT}"))
+      }
+    }
+  };
+  NetworkStatus {
+    online: reason.is_none(),
+    reason,
+    interfaces: interfaces(),
+  }
+}
+
+/// Interfaces with a non-loopback address, sorted by name.
+fn interfaces() -> Vec<NetworkInterface> {
+  let networks = sysinfo::Networks::new_with_refreshed_list();
+  let mut interfaces: Vec<NetworkInterface> = networks
+    .list()
+    .iter()
+    .filter_map(|(name, data)| {
+      let addresses: Vec<String> = data
+        .ip_networks()
+        .iter()
+        .filter(|network| !network.addr.is_loopback())
+        .map(|network| format!("{}/{}", network.addr, network.prefix))
+        .collect();
+      (!addresses.is_empty()).then(|| NetworkInterface {
+        name: name.clone(),
+        addresses,
+      })
+    })
+    .collect();
+  interfaces.sort_by(|a, b| a.name.cmp(&b.name));
+  interfaces
+}
@@ -0,0 +1,305 @@
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use serde_json::{Map, Value};
+use tauri::{AppHandle, Emitter, Manager, State};
+
+use crate::dialogs::{self, FileFilter, FilterPreset};
+
+const PREFERENCES_FILE: &str = "preferences.json";
+const EXPORT_FILE_NAME: &str = "pluto-duck-preferences.json";
+
+pub const MINIMIZE_TO_TRAY: &str = "minimize_to_tray";
+/// Popover-style utility mode: the main window hides when it loses focus.
+pub const HIDE_ON_BLUR: &str = "hide_on_blur";
+pub const ACCENT_COLOR: &str = "accent_color";
+/// Accelerator string such as `CmdOrCtrl+Shift+D`; an empty string disables the shortcut.
+pub const GLOBAL_SHORTCUT: &str = "global_shortcut";
+pub const DEVELOPER_MODE: &str = "developer_mode";
+/// When false, hiding the window stops the backend and showing it again restarts it.
+pub const KEEP_SERVER_RUNNING_WHEN_HIDDEN: &str = "keep_server_running_when_hidden";
+/// Pause the backend (Unix only) once every window has been hidden and idle for a while.
+pub const SUSPEND_WHEN_HIDDEN: &str = "suspend_when_hidden";
+/// Seconds hidden before `suspend_when_hidden` pauses the backend; five minutes if unset.
+pub const SUSPEND_IDLE_SECS: &str = "suspend_idle_secs";
+/// Keep backend logs across launches, separated per session, instead of truncating them.
+pub const APPEND_LOGS: &str = "append_logs";
+/// Opt-in: write a local report (exit status, log tail, versions) when the backend crashes.
+pub const CRASH_REPORTS: &str = "crash_reports";
+/// Directory downloads are saved to without asking; empty means show the save dialog.
+pub const DOWNLOAD_DIR: &str = "download_dir";
+/// `{min, max}` from `set_size_constraints` with `persist`, re-applied to the main window.
+pub const SIZE_CONSTRAINTS: &str = "size_constraints";
+/// Name of the display chosen with `move_to_monitor`; the main window reopens there.
+pub const MONITOR: &str = "monitor";
+/// Developer-only replacement for the default webview User-Agent; read at window creation.
+pub const USER_AGENT_OVERRIDE: &str = "user_agent_override";
+
+#[derive(Clone, Copy)]
+enum Kind {
+  Bool,
+  Number,
+  String,
+  /// Structured values such as `size_constraints`; their owner checks the shape on use.
+  Object,
+}
+
+/// Keys only their own commands may change, because setting them unlocks something
+/// (`set_developer_mode` asks the user first). `set_preference` rejects them and an import
+/// leaves them alone.
+const RESERVED: &[&str] = &[DEVELOPER_MODE];
+
+/// Every key the shell understands; anything else in an imported file is dropped.
+const SCHEMA: &[(&str, Kind)] = &[
+  (MINIMIZE_TO_TRAY, Kind::Bool),
+  (HIDE_ON_BLUR, Kind::Bool),
+  (ACCENT_COLOR, Kind::String),
+  (GLOBAL_SHORTCUT, Kind::String),
+  (DEVELOPER_MODE, Kind::Bool),
+  (KEEP_SERVER_RUNNING_WHEN_HIDDEN, Kind::Bool),
+  (SUSPEND_WHEN_HIDDEN, Kind::Bool),
+  (SUSPEND_IDLE_SECS, Kind::Number),
+  (CRASH_REPORTS, Kind::Bool),
+  (APPEND_LOGS, Kind::Bool),
+  (DOWNLOAD_DIR, Kind::String),
+  (SIZE_CONSTRAINTS, Kind::Object),
+  (MONITOR, Kind::String),
+  (USER_AGENT_OVERRIDE, Kind::String),
+];
+
+/// Shell-side preferences persisted as a flat JSON object in the app config directory.
+///
+/// These only cover native behavior (window chrome, tray, shortcuts); product settings
+/// live in the backend.
+pub struct Preferences {
+  path: PathBuf,
+  values: Mutex<Value>,
+}
+
+impl Preferences {
+  pub fn load(app: &AppHandle) -> Self {
+    let path = app
+      .path()
+      .app_config_dir()
+      .unwrap_or_else(|_| std::env::temp_dir().join("pluto_duck"))
+      .join(PREFERENCES_FILE);
+    let values = read_preferences(&path);
+    Self {
+      path,
+      values: Mutex::new(values),
+    }
+  }
+
+  pub fn get(&self, key: &str) -> Option<Value> {
+    self.values.lock().ok()?.get(key).cloned()
+  }
+
+  pub fn get_bool(&self, key: &str, default: bool) -> bool {
+    self.get(key).and_then(|value| value.as_bool()).unwrap_or(default)
+  }
+
+  pub fn get_u64(&self, key: &str) -> Option<u64> {
+    self.get(key).and_then(|value| value.as_u64())
+  }
+
+  pub fn snapshot(&self) -> Value {
+    self
+      .values
+      .lock()
+      .map(|values| values.clone())
+      .unwrap_or_else(|_| Value::Object(Map::new()))
+  }
+
+  pub fn set(&self, key: &str, value: Value) -> Result<(), String> {
+    let mut values = self.values.lock().map_err(|err| err.to_string())?;
+    if let Value::Object(map) = &mut *values {
+      map.insert(key.to_string(), value);
+    }
+    self.persist(&values)
+  }
+
+  /// Replaces the in-memory values with the file's, for edits made outside the app. Unlike
+  /// at startup a malformed file is an error, so a typo doesn't wipe the running settings.
+  pub fn reload(&self) -> Result<(), String> {
+    let reloaded = match std::fs::read_to_string(&self.path) {
+      Ok(contents) => match serde_json::from_str::<Value>(&contents) {
+        Ok(value @ Value::Object(_)) => value,
+        Ok(_) => return Err("Preferences file must contain a JSON object".to_string()),
+        Err(err) => return Err(format!("Preferences file is not valid JSON: {err}")),
+      },
+      Err(err) if err.kind() == std::io::ErrorKind::NotFound => Value::Object(Map::new()),
+      Err(err) => return Err(format!("Failed to read preferences: {err}")),
+    };
+    *self.values.lock().map_err(|err| err.to_string())? = reloaded;
+    Ok(())
+  }
+
+  /// Merges known keys from `incoming` after type-checking all of them; nothing is written
+  /// if any known key has the wrong type.
+  pub fn import(&self, incoming: &Map<String, Value>) -> Result<(), String> {
+    let mut accepted = Map::new();
+    for (key, kind) in SCHEMA {
+      if RESERVED.contains(key) {
+        continue;
+      }
+      let Some(value) = incoming.get(*key) else {
+        continue;
+      };
+      // `null` is how a cleared setting (e.g. `set_user_agent(null)`) is stored.
+      let valid = value.is_null()
+        || match kind {
+          Kind::Bool => value.is_boolean(),
+          Kind::Number => value.is_u64(),
+          Kind::String => value.is_string(),
+          Kind::Object => value.is_object(),
+        };
+      if !valid {
+        return Err(format!("Invalid value for preference {key}: {value}"));
+      }
+      accepted.insert(key.to_string(), value.clone());
+    }
+    let ignored = incoming.len() - accepted.len();
+    if ignored > 0 {
+      log::info!("ignoring {ignored} unknown or reserved preference key(s) on import");
+    }
+
+    let mut values = self.values.lock().map_err(|err| err.to_string())?;
+    if let Value::Object(map) = &mut *values {
+      map.extend(accepted);
+    }
+    self.persist(&values)
+  }
+
+  fn persist(&self, values: &Value) -> Result<(), String> {
+    if let Some(parent) = self.path.parent() {
+      std::fs::create_dir_all(parent)
+        .map_err(|err| format!("Failed to create preferences directory: {err}"))?;
+    }
+    let serialized = serde_json::to_string_pretty(values).map_err(|err| err.to_string())?;
+    std::fs::write(&self.path, serialized)
+      .map_err(|err| format!("Failed to write preferences: {err}"))
+  }
+}
+
+fn read_preferences(path: &Path) -> Value {
+  let contents = match std::fs::read_to_string(path) {
+    Ok(contents) => contents,
+    Err(_) => return Value::Object(Map::new()),
+  };
+  match serde_json::from_str::<Value>(&contents) {
+    Ok(value @ Value::Object(_)) => value,
+    Ok(_) | Err(_) => {
+      log::warn!("ignoring malformed preferences file at {}", path.display());
+      Value::Object(Map::new())
+    }
+  }
+}
+
+/// Re-applies the preferences that affect the running app without a restart.
+pub fn apply_live_settings(app: &AppHandle) {
+  if let Some(window) = app.get_webview_window("main") {
+    crate::appearance::restore_accent_color(&window);
+    crate::window::restore_size_constraints(&window);
+  }
+  crate::shortcut::register_from_preferences(app);
+}
+
+#[tauri::command]
+pub fn get_preferences(preferences: State<'_, Preferences>) -> Value {
+  preferences.snapshot()
+}
+
+/// Applies one changed key to the running app. Keys read on every use, such as
+/// `minimize_to_tray` and `hide_on_blur`, need nothing here.
+fn apply_live_setting(app: &AppHandle, key: &str) {
+  if key == GLOBAL_SHORTCUT {
+    crate::shortcut::register_from_preferences(app);
+    return;
+  }
+  let Some(window) = app.get_webview_window("main") else {
+    return;
+  };
+  match key {
+    ACCENT_COLOR => crate::appearance::restore_accent_color(&window),
+    SIZE_CONSTRAINTS => crate::window::restore_size_constraints(&window),
+    _ => {}
+  }
+}
+
+#[tauri::command]
+pub fn set_preference(app: AppHandle, key: String, value: Value) -> Result<(), String> {
+  if RESERVED.contains(&key.as_str()) {
+    return Err(format!("Preference {key} can't be set directly"));
+  }
+  let preferences = app.state::<Preferences>();
+  let changed = preferences.get(&key).as_ref() != Some(&value);
+  preferences.set(&key, value)?;
+  if changed {
+    apply_live_setting(&app, &key);
+  }
+  Ok(())
+}
+
+/// Re-reads the preferences file after it was edited by hand, applies the live settings and
+/// emits `preferences-reloaded` with the new values.
+#[tauri::command]
+pub fn reload_preferences(app: AppHandle) -> Result<(), String> {
+  let preferences = app.state::<Preferences>();
+  preferences.reload()?;
+  log::info!("preferences reloaded from {}", preferences.path.display());
+  apply_live_settings(&app);
+  let _ = app.emit("preferences-reloaded", preferences.snapshot());
+  Ok(())
+}
+
+/// Writes preferences to `path`, or to a location picked in a save dialog. Returns the path
+/// written, or `None` if the dialog was cancelled.
+#[tauri::command]
+pub async fn export_preferences(
+  app: AppHandle,
+  path: Option<PathBuf>,
+) -> Result<Option<PathBuf>, String> {
+  let path = match path {
+    Some(path) => path,
+    None => match dialogs::save_file_path(
+      &app,
+      Some(EXPORT_FILE_NAME),
+      &[FileFilter::Preset(FilterPreset::Preferences)],
+    )? {
+      Some(picked) => picked,
+      None => return Ok(None),
+    },
+  };
+
+  let snapshot = app.state::<Preferences>().snapshot();
+  let serialized = serde_json::to_string_pretty(&snapshot).map_err(|err| err.to_string())?;
+  std::fs::write(&path, serialized).map_err(|err| format!("Failed to export preferences: {err}"))?;
+  Ok(Some(path))
+}
+
+/// Loads preferences from `path` or a picked file, then applies the live ones. Returns
+/// `false` if the dialog was cancelled.
+#[tauri::command]
+pub async fn import_preferences(app: AppHandle, path: Option<PathBuf>) -> Result<bool, String> {
+  let path = match path {
+    Some(path) => path,
+    None => {
+      match dialogs::pick_file_path(&app, &[FileFilter::Preset(FilterPreset::Preferences)])? {
+        Some(picked) => picked,
+        None => return Ok(false),
+      }
+    }
+  };
+
+  let contents =
+    std::fs::read_to_string(&path).map_err(|err| format!("Failed to read preferences: {err}"))?;
+  let incoming = match serde_json::from_str::<Value>(&contents) {
+    Ok(Value::Object(map)) => map,
+    Ok(_) => return Err("Preferences file must contain a JSON object".to_string()),
+    Err(err) => return Err(format!("Preferences file is not valid JSON: {err}")),
+  };
+
+  app.state::<Preferences>().import(&incoming)?;
+  apply_live_settings(&app);
+  Ok(true)
+}
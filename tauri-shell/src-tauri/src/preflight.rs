@@ -0,0 +1,126 @@
+use std::net::{Ipv4Addr, TcpListener};
+use std::path::Path;
+
+use serde::Serialize;
+use tauri::{AppHandle, Manager};
+
+use crate::{backend, integrity};
+
+const PROBE_FILE: &str = ".preflight-write-test";
+const MIN_FREE_DISK_BYTES: u64 = 500 * 1024 * 1024;
+
+#[derive(Serialize)]
+pub struct PreflightCheck {
+  pub name: &'static str,
+  pub passed: bool,
+  pub message: String,
+}
+
+#[derive(Serialize)]
+pub struct PreflightReport {
+  pub passed: bool,
+  pub checks: Vec<PreflightCheck>,
+}
+
+impl PreflightCheck {
+  fn new(name: &'static str, result: Result<String, String>) -> Self {
+    match result {
+      Ok(message) => Self {
+        name,
+        passed: true,
+        message,
+      },
+      Err(message) => Self {
+        name,
+        passed: false,
+        message,
+      },
+    }
+  }
+}
+
+/// Runs the launch prerequisites without starting anything, for a first-run or support
+/// screen: bundled backend present and intact, data root writable, port free, enough disk.
+#[tauri::command]
+pub fn preflight_check(app: AppHandle) -> PreflightReport {
+  let data_root = backend::resolve_data_root(&app);
+  let binary = backend::backend_binary_path(&app).map_err(|err| err.to_string());
+
+  let mut checks = vec![PreflightCheck::new(
+    "backend_binary",
+    binary.as_ref().map(|path| format!("found {}", path.display())).map_err(Clone::clone),
+  )];
+  let onedir = binary.as_ref().ok().filter(|path| !backend::is_sidecar(path));
+  if let Some(backend_dir) = onedir.and_then(|path| path.parent()) {
+    checks.push(PreflightCheck::new(
+      "install_integrity",
+      integrity::verify_backend_dir(backend_dir)
+        .map(|()| "bundled backend files are complete".to_string())
+        .map_err(|err| err.to_string()),
+    ));
+  }
+  checks.push(PreflightCheck::new("data_root_writable", check_writable(&data_root)));
+  checks.push(PreflightCheck::new("port_available", check_port(&app)));
+  checks.push(PreflightCheck::new("disk_space", check_disk_space(&data_root)));
+
+  PreflightReport {
+    passed: checks.iter().all(|check| check.passed),
+    checks,
+  }
+}
+
+fn check_writable(data_root: &Path) -> Result<String, String> {
+  let probe = data_root.join(PROBE_FILE);
+  std::fs::write(&probe, b"ok")
+    .map_err(|err| format!("{} is not writable: {err}", data_root.display()))?;
+  let _ = std::fs::remove_file(&probe);
+  Ok(format!("{} is writable", data_root.display()))
+}
+
+fn check_port(app: &AppHandle) -> Result<String, String> {
+  let port = backend::port();
+  let running = app
+    .try_state::<backend::BackendState>()
+    .is_some_and(|state| backend::is_running(&state));
+  if running {
+    return Ok(format!("port {port} is in use by the running backend"));
+  }
+  TcpListener::bind((Ipv4Addr::LOCALHOST, port))
+    .map(|_| format!("port {port} is free"))
+    .map_err(|err| format!("port {port} is unavailable: {err}"))
+}
+
+fn check_disk_space(data_root: &Path) -> Result<String, String> {
+  let Some(available) = available_disk_bytes(data_root) else {
+    return Ok("free disk space could not be determined on this platform".to_string());
+  };
+  let available_mb = available / (1024 * 1024);
+  if available < MIN_FREE_DISK_BYTES {
+    Err(format!(
+      "only {available_mb} MB free; at least {} MB is recommended",
+      MIN_FREE_DISK_BYTES / (1024 * 1024)
+    ))
+  } else {
+    Ok(format!("{available_mb} MB free"))
+  }
+}
+
+#[cfg(unix)]
+#[allow(clippy::unnecessary_cast)] // statvfs field widths differ between platforms.
+fn available_disk_bytes(path: &Path) -> Option<u64> {
+  use std::os::unix::ffi::OsStrExt;
+
+  let path = std::ffi::CString::new(path.as_os_str().as_bytes()).ok()?;
+  let mut stat = std::mem::MaybeUninit::<libc::statvfs>::uninit();
+  // SAFETY: `path` is a valid NUL-terminated string and `stat` is only read after success.
+  if unsafe { libc::statvfs(path.as_ptr(), stat.as_mut_ptr()) } != 0 {
+    return None;
+  }
+  let stat = unsafe { stat.assume_init() };
+  Some(stat.f_bavail as u64 * stat.f_frsize as u64)
+}
+
+#[cfg(not(unix))]
+fn available_disk_bytes(_path: &Path) -> Option<u64> {
+  None
+}
@@ -0,0 +1,109 @@
+//! OS-level helpers for signalling the backend process by pid.
+
+#[cfg(unix)]
+fn send_signal(pid: u32, signal: libc::c_int) -> Result<(), String> {
+  let pid = libc::pid_t::try_from(pid).map_err(|_| format!("Invalid pid {pid}"))?;
+  // SAFETY: kill(2) has no memory-safety preconditions; failures are reported through errno.
+  if unsafe { libc::kill(pid, signal) } == 0 {
+    Ok(())
+  } else {
+    Err(format!("Failed to signal process {pid}: {}", std::io::Error::last_os_error()))
+  }
+}
+
+/// Asks the process to exit cleanly (SIGTERM). Platforms without signals report an error so
+/// callers fall back to killing it.
+pub fn terminate(pid: u32) -> Result<(), String> {
+  #[cfg(unix)]
+  {
+    send_signal(pid, libc::SIGTERM)?;
+    // A process paused with `suspend` only acts on the SIGTERM once continued.
+    send_signal(pid, libc::SIGCONT)
+  }
+  #[cfg(not(unix))]
+  {
+    let _ = pid;
+    Err("Graceful termination is not supported on this platform".to_string())
+  }
+}
+
+/// Ends the process immediately (SIGKILL, or `TerminateProcess` on Windows), for when
+/// waiting on a graceful exit is what hangs.
+pub fn kill(pid: u32) -> Result<(), String> {
+  #[cfg(unix)]
+  {
+    send_signal(pid, libc::SIGKILL)
+  }
+  #[cfg(windows)]
+  {
+    use windows_sys::Win32::Foundation::CloseHandle;
+    use windows_sys::Win32::System::Threading::{OpenProcess, TerminateProcess, PROCESS_TERMINATE};
+
+    // SAFETY: the handle is checked before use and closed on every path.
+    unsafe {
+      let handle = OpenProcess(PROCESS_TERMINATE, 0, pid);
+      if handle.is_null() {
+        return Err(format!("Failed to open process {pid}: {}", std::io::Error::last_os_error()));
+      }
+      let killed = TerminateProcess(handle, 1) != 0;
+      let err = std::io::Error::last_os_error();
+      CloseHandle(handle);
+      if killed {
+        Ok(())
+      } else {
+        Err(format!("Failed to kill process {pid}: {err}"))
+      }
+    }
+  }
+}
+
+/// Stops the process in place (SIGSTOP) so its state can be inspected.
+pub fn suspend(pid: u32) -> Result<(), String> {
+  #[cfg(unix)]
+  {
+    send_signal(pid, libc::SIGSTOP)
+  }
+  #[cfg(not(unix))]
+  {
+    let _ = pid;
+    Err("Suspending the backend is not supported on this platform".to_string())
+  }
+}
+
+/// Continues a process previously stopped with [`suspend`].
+pub fn resume(pid: u32) -> Result<(), String> {
+  #[cfg(unix)]
+  {
+    send_signal(pid, libc::SIGCONT)
+  }
+  #[cfg(not(unix))]
+  {
+    let _ = pid;
+    Err("Resuming the backend is not supported on this platform".to_string())
+  }
+}
+
+/// Resident set size of the process in bytes, where the platform makes it cheap to read.
+pub fn rss_bytes(pid: u32) -> Option<u64> {
+  #[cfg(target_os = "linux")]
+  {
+    let status = std::fs::read_to_string(format!("/proc/{pid}/status")).ok()?;
+    let line = status.lines().find(|line| line.starts_with("VmRSS:"))?;
+    let kib: u64 = line.split_whitespace().nth(1)?.parse().ok()?;
+    Some(kib * 1024)
+  }
+  #[cfg(target_os = "macos")]
+  {
+    let output = std::process::Command::new("ps")
+      .args(["-o", "rss=", "-p", &pid.to_string()])
+      .output()
+      .ok()?;
+    let kib: u64 = String::from_utf8_lossy(&output.stdout).trim().parse().ok()?;
+    Some(kib * 1024)
+  }
+  #[cfg(not(any(target_os = "linux", target_os = "macos")))]
+  {
+    let _ = pid;
+    None
+  }
+}
@@ -0,0 +1,153 @@
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Condvar, Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+use tauri::{AppHandle, Manager, State, WebviewWindow};
+
+use crate::{deeplink, startup_trace, tasks};
+
+const APP_MOUNTED_TIMEOUT_ENV: &str = "PLUTODUCK_APP_MOUNTED_TIMEOUT_MS";
+/// First delay between startup polls; a fast machine is usually ready within a few of them.
+const POLL_INITIAL_INTERVAL: Duration = Duration::from_millis(50);
+/// The delay stops doubling here, so a slow machine isn't polled harder than it needs.
+const POLL_MAX_INTERVAL: Duration = Duration::from_millis(400);
+
+static PROCESS_STARTED: OnceLock<Instant> = OnceLock::new();
+/// Milliseconds from process start to the first `notify_app_mounted`; 0 until then.
+static STARTUP_DURATION_MS: AtomicU64 = AtomicU64::new(0);
+
+/// Set once the app starts exiting, so startup waits give up instead of holding up teardown.
+static SHUTDOWN_REQUESTED: AtomicBool = AtomicBool::new(false);
+
+/// Cancels pending startup waits: the mount wait wakes immediately and polling loops stop at
+/// their next iteration.
+pub fn request_shutdown(app: &AppHandle) {
+  SHUTDOWN_REQUESTED.store(true, Ordering::SeqCst);
+  if let Some(mounted) = app.try_state::<AppMounted>() {
+    // Take the lock so a waiter between its check and its wait can't miss the wakeup.
+    let _guard = mounted.mounted.lock();
+    mounted.signal.notify_all();
+  }
+}
+
+pub fn shutdown_requested() -> bool {
+  SHUTDOWN_REQUESTED.load(Ordering::SeqCst)
+}
+
+/// Called first thing in `run()`; startup time is measured from here.
+pub fn record_process_start() {
+  PROCESS_STARTED.get_or_init(Instant::now);
+}
+
+pub fn startup_duration_ms() -> u64 {
+  STARTUP_DURATION_MS.load(Ordering::SeqCst)
+}
+
+/// Set once the frontend reports it is interactive, a stronger signal than the page loading.
+#[derive(Default)]
+pub struct AppMounted {
+  mounted: Mutex<bool>,
+  signal: Condvar,
+}
+
+impl AppMounted {
+  pub fn mark(&self) {
+    if let Ok(mut mounted) = self.mounted.lock() {
+      *mounted = true;
+      self.signal.notify_all();
+    }
+  }
+
+  pub fn is_mounted(&self) -> bool {
+    self.mounted.lock().map(|mounted| *mounted).unwrap_or(false)
+  }
+
+  /// Blocks until the frontend mounts, `timeout` elapses or shutdown is requested; returns
+  /// whether it mounted.
+  pub fn wait(&self, timeout: Duration) -> bool {
+    let Ok(guard) = self.mounted.lock() else {
+      return false;
+    };
+    self
+      .signal
+      .wait_timeout_while(guard, timeout, |mounted| !*mounted && !shutdown_requested())
+      .map(|(mounted, _)| *mounted)
+      .unwrap_or(false)
+  }
+}
+
+/// Delay for loops polling for the backend to come up: short at first, doubling on every miss
+/// up to [`POLL_MAX_INTERVAL`].
+pub struct PollBackoff(Duration);
+
+impl Default for PollBackoff {
+  fn default() -> Self {
+    Self(POLL_INITIAL_INTERVAL)
+  }
+}
+
+impl PollBackoff {
+  pub fn sleep(&mut self) {
+    std::thread::sleep(self.next_delay());
+  }
+
+  fn next_delay(&mut self) -> Duration {
+    let delay = self.0;
+    self.0 = (self.0 * 2).min(POLL_MAX_INTERVAL);
+    delay
+  }
+}
+
+/// When set, the main window stays hidden until the frontend mounts (or the timeout passes)
+/// so slow first loads don't flash a half-rendered page.
+pub fn mount_timeout() -> Option<Duration> {
+  std::env::var(APP_MOUNTED_TIMEOUT_ENV)
+    .ok()
+    .and_then(|value| value.trim().parse::<u64>().ok())
+    .filter(|millis| *millis > 0)
+    .map(Duration::from_millis)
+}
+
+pub fn reveal_when_mounted(window: WebviewWindow, timeout: Duration) {
+  let _ = window.hide();
+  tasks::spawn("reveal-when-mounted", move || {
+    let mounted = window.state::<AppMounted>().wait(timeout);
+    if shutdown_requested() {
+      return;
+    }
+    startup_trace::record("reveal", || format!("showing main window (mounted: {mounted})"));
+    if mounted {
+      log::info!("frontend mounted; showing main window");
+    } else {
+      log::warn!("frontend did not report mounted within {timeout:?}; showing main window anyway");
+    }
+    crate::window::show_fading_in(&window);
+    let _ = window.set_focus();
+  });
+}
+
+/// Also re-delivers auth callbacks the frontend hasn't acknowledged, on every mount.
+#[tauri::command]
+pub fn notify_app_mounted(app: AppHandle, state: State<'_, AppMounted>) {
+  if !state.is_mounted() {
+    let elapsed = PROCESS_STARTED.get().map(Instant::elapsed).unwrap_or_default();
+    let millis = u64::try_from(elapsed.as_millis()).unwrap_or(u64::MAX).max(1);
+    STARTUP_DURATION_MS.store(millis, Ordering::SeqCst);
+    log::info!("frontend reported app mounted; startup took {millis} ms");
+    startup_trace::finish(&format!("frontend mounted after {millis} ms"));
+  }
+  state.mark();
+  deeplink::redeliver_pending(&app);
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn poll_backoff_doubles_up_to_the_cap() {
+    let mut backoff = PollBackoff::default();
+    let delays: Vec<u128> = (0..6).map(|_| backoff.next_delay().as_millis()).collect();
+    assert_eq!(delays, [50, 100, 200, 400, 400, 400]);
+  }
+}
@@ -0,0 +1,77 @@
+//! Recently opened items reported by the backend, mirrored into the tray menu.
+
+use std::sync::Mutex;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use tauri::{App, AppHandle, Emitter, Listener, Manager, State};
+
+use crate::{api, tray};
+
+const RECENT_PATH: &str = "/api/v1/recent";
+const RECENT_TIMEOUT: Duration = Duration::from_secs(5);
+const MAX_RECENT_ITEMS: usize = 10;
+
+#[derive(Clone, Deserialize, Serialize)]
+pub struct RecentItem {
+  pub id: String,
+  pub name: String,
+}
+
+#[derive(Default)]
+pub struct RecentItems(Mutex<Vec<RecentItem>>);
+
+/// Refreshes the cached list whenever the frontend emits `recent-files-changed`.
+pub fn install(app: &App) {
+  let handle = app.handle().clone();
+  app.listen("recent-files-changed", move |_| refresh(handle.clone()));
+}
+
+pub fn refresh(app: AppHandle) {
+  tauri::async_runtime::spawn(async move {
+    let items = match fetch().await {
+      Ok(items) => items,
+      Err(err) => {
+        log::warn!("failed to refresh recent files: {err}");
+        return;
+      }
+    };
+    if let Ok(mut cached) = app.state::<RecentItems>().0.lock() {
+      *cached = items.clone();
+    }
+    if let Err(err) = tray::set_recent_items(&app, &items) {
+      log::warn!("failed to update tray recent files: {err}");
+    }
+  });
+}
+
+async fn fetch() -> Result<Vec<RecentItem>, String> {
+  let response = api::client(RECENT_TIMEOUT)?
+    .get(api::url(RECENT_PATH))
+    .send()
+    .await
+    .map_err(|err| format!("Backend request to {RECENT_PATH} failed: {err}"))?;
+  // Backends that don't track recent items yet simply have no list to show.
+  if response.status() == reqwest::StatusCode::NOT_FOUND {
+    return Ok(Vec::new());
+  }
+  let mut items: Vec<RecentItem> = response
+    .error_for_status()
+    .map_err(|err| format!("Backend rejected {RECENT_PATH}: {err}"))?
+    .json()
+    .await
+    .map_err(|err| format!("Invalid recent files payload: {err}"))?;
+  items.truncate(MAX_RECENT_ITEMS);
+  Ok(items)
+}
+
+/// Brings the window forward and asks the frontend to open the item via `open-recent-file`.
+pub fn open(app: &AppHandle, id: &str) {
+  crate::show_and_focus_main_window(app);
+  let _ = app.emit("open-recent-file", id);
+}
+
+#[tauri::command]
+pub fn recent_files(state: State<'_, RecentItems>) -> Vec<RecentItem> {
+  state.0.lock().map(|items| items.clone()).unwrap_or_default()
+}
@@ -0,0 +1,121 @@
+//! Recovery from the webview's content process dying or hanging. Left alone the window goes
+//! blank (WebView2, WebKitGTK) or silently reloads (WKWebView); instead the shell swaps in an
+//! embedded page with a Reload button and emits `webview-crashed` to the other windows.
+
+use serde::Serialize;
+use tauri::{Emitter, Manager, Runtime, Webview};
+
+use crate::{assets, tasks};
+
+#[derive(Clone, Serialize)]
+pub struct WebviewCrash {
+  pub label: String,
+  /// `crashed`, `unresponsive`, `out-of-memory` or `terminated`.
+  pub reason: &'static str,
+  pub url: Option<String>,
+}
+
+/// Handles a crash reported on the UI thread. The page change is posted from another thread so
+/// it isn't issued from inside the webview's own event callback.
+pub fn handle_crash<R: Runtime>(webview: Webview<R>, reason: &'static str) {
+  tasks::spawn("webview-crash", move || recover(&webview, reason));
+}
+
+fn recover<R: Runtime>(webview: &Webview<R>, reason: &'static str) {
+  let label = webview.label().to_string();
+  let url = webview.url().ok();
+  log::error!("webview {label} content process {reason} at {url:?}");
+  let _ = webview.app_handle().emit(
+    "webview-crashed",
+    WebviewCrash {
+      label: label.clone(),
+      reason,
+      url: url.as_ref().map(|url| url.to_string()),
+    },
+  );
+
+  // Already on an embedded page (possibly this one); reloading it is all that's left to do.
+  if url.as_ref().is_some_and(assets::is_asset_url) {
+    if let Err(err) = webview.reload() {
+      log::warn!("failed to reload webview {label}: {err}");
+    }
+    return;
+  }
+  let mut crash_page = assets::url(assets::WEBVIEW_CRASHED_PAGE);
+  if let Some(url) = &url {
+    crash_page.query_pairs_mut().append_pair("url", url.as_str());
+  }
+  if let Err(err) = webview.navigate(crash_page) {
+    log::warn!("failed to show the crash page in webview {label}: {err}");
+  }
+}
+
+/// Hooks the platform's content process termination signal for `window`. macOS goes through
+/// the builder instead (`Builder::on_web_content_process_terminate`).
+pub fn watch(window: &tauri::WebviewWindow) {
+  #[cfg(target_os = "linux")]
+  {
+    use webkit2gtk::{WebProcessTerminationReason, WebViewExt};
+
+    let webview: Webview = window.as_ref().clone();
+    let watched = window.with_webview(move |platform| {
+      platform
+        .inner()
+        .connect_web_process_terminated(move |_, termination| {
+          let reason = match termination {
+            WebProcessTerminationReason::ExceededMemoryLimit => "out-of-memory",
+            WebProcessTerminationReason::TerminatedByApi => "terminated",
+            _ => "crashed",
+          };
+          handle_crash(webview.clone(), reason);
+        });
+    });
+    if let Err(err) = watched {
+      log::warn!("failed to watch the webview content process: {err}");
+    }
+  }
+  #[cfg(windows)]
+  {
+    use webview2_com::Microsoft::Web::WebView2::Win32::{
+      COREWEBVIEW2_PROCESS_FAILED_KIND, COREWEBVIEW2_PROCESS_FAILED_KIND_RENDER_PROCESS_EXITED,
+      COREWEBVIEW2_PROCESS_FAILED_KIND_RENDER_PROCESS_UNRESPONSIVE,
+    };
+    use webview2_com::ProcessFailedEventHandler;
+
+    let webview: Webview = window.as_ref().clone();
+    let watched = window.with_webview(move |platform| {
+      let handler = ProcessFailedEventHandler::create(Box::new(move |_, args| {
+        let Some(args) = args else {
+          return Ok(());
+        };
+        let mut kind = COREWEBVIEW2_PROCESS_FAILED_KIND::default();
+        unsafe { args.ProcessFailedKind(&mut kind)? };
+        // Other kinds (GPU, utility processes) are recovered by WebView2 itself.
+        let reason = match kind {
+          COREWEBVIEW2_PROCESS_FAILED_KIND_RENDER_PROCESS_EXITED => "crashed",
+          COREWEBVIEW2_PROCESS_FAILED_KIND_RENDER_PROCESS_UNRESPONSIVE => "unresponsive",
+          _ => return Ok(()),
+        };
+        handle_crash(webview.clone(), reason);
+        Ok(())
+      }));
+      let mut token = 0i64;
+      let added = unsafe {
+        platform
+          .controller()
+          .CoreWebView2()
+          .and_then(|core| core.add_ProcessFailed(&handler, &mut token))
+      };
+      if let Err(err) = added {
+        log::warn!("failed to watch the webview content process: {err}");
+      }
+    });
+    if let Err(err) = watched {
+      log::warn!("failed to watch the webview content process: {err}");
+    }
+  }
+  #[cfg(target_os = "macos")]
+  {
+    let _ = window;
+  }
+}
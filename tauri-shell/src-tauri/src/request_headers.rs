@@ -0,0 +1,109 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use serde::Serialize;
+use tauri::plugin::TauriPlugin;
+use tauri::{AppHandle, Manager, Runtime, State};
+
+use crate::backend;
+
+const INIT_SCRIPT: &str = include_str!("scripts/request_headers.js");
+
+/// Extra headers the webview attaches to fetch() calls targeting the page's own origin or the
+/// backend's.
+#[derive(Default)]
+pub struct RequestHeaders(Mutex<HashMap<String, String>>);
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RequestHeaderConfig {
+  pub headers: HashMap<String, String>,
+  /// Exactly `scheme://host:port`, compared against each request's origin.
+  pub backend_origin: String,
+}
+
+/// Registers the init script that patches `fetch` before any page script runs.
+pub fn init<R: Runtime>() -> TauriPlugin<R> {
+  tauri::plugin::Builder::new("request-headers")
+    .js_init_script(INIT_SCRIPT)
+    .build()
+}
+
+#[tauri::command]
+pub fn get_request_headers(state: State<'_, RequestHeaders>) -> RequestHeaderConfig {
+  RequestHeaderConfig {
+    headers: state.0.lock().map(|headers| headers.clone()).unwrap_or_default(),
+    backend_origin: backend::origin(),
+  }
+}
+
+/// Tells loaded pages the backend's current origin after it moved to another port.
+pub fn publish_backend_origin(app: &AppHandle) {
+  let Ok(origin) = serde_json::to_string(&backend::origin()) else {
+    return;
+  };
+  let script = format!("window.__plutoBackendOrigin = {origin};");
+  for (_, window) in app.webview_windows() {
+    let _ = window.eval(&script);
+  }
+}
+
+#[tauri::command]
+pub fn set_request_headers(
+  app: AppHandle,
+  state: State<'_, RequestHeaders>,
+  headers: HashMap<String, String>,
+) -> Result<(), String> {
+  for (name, value) in &headers {
+    validate_header(name, value)?;
+  }
+
+  let serialized = serde_json::to_string(&headers).map_err(|err| err.to_string())?;
+  *state.0.lock().map_err(|err| err.to_string())? = headers;
+
+  // Already-loaded pages pick the new set up immediately; reloads fetch it via the init script.
+  let script = format!("window.__plutoRequestHeaders = {serialized};");
+  for (_, window) in app.webview_windows() {
+    let _ = window.eval(&script);
+  }
+  Ok(())
+}
+
+fn validate_header(name: &str, value: &str) -> Result<(), String> {
+  let valid_name = !name.is_empty()
+    && name
+      .bytes()
+      .all(|byte| byte.is_ascii_alphanumeric() || b"!#$%&'*+-.^_`|~".contains(&byte));
+  if !valid_name {
+    return Err(format!("Invalid header name: {name:?}"));
+  }
+  if value.bytes().any(|byte| byte == b'\r' || byte == b'\n' || byte == 0) {
+    return Err(format!("Invalid value for header {name}"));
+  }
+  Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn accepts_token_names_and_plain_values() {
+    assert!(validate_header("X-Pluto-Trace", "abc 123").is_ok());
+    assert!(validate_header("x_custom!", "").is_ok());
+  }
+
+  #[test]
+  fn rejects_invalid_names() {
+    for name in ["", "X Header", "X:Header", "X-H\u{e9}ader"] {
+      assert!(validate_header(name, "value").is_err(), "{name:?} should be rejected");
+    }
+  }
+
+  #[test]
+  fn rejects_values_that_could_split_the_request() {
+    for value in ["a\r\nX-Injected: 1", "a\nb", "a\0b"] {
+      assert!(validate_header("X-Header", value).is_err(), "{value:?} should be rejected");
+    }
+  }
+}
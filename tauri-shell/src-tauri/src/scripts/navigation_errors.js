@@ -0,0 +1,25 @@
+// Reports resources the page's Content-Security-Policy blocked, once per directive and URI,
+// so a blank page caused by CSP shows up in the logs instead of looking like a dead backend.
+(function () {
+  if (window.__plutoNavigationErrorsInstalled) {
+    return;
+  }
+  window.__plutoNavigationErrorsInstalled = true;
+
+  var reported = {};
+  document.addEventListener('securitypolicyviolation', function (event) {
+    var internals = window.__TAURI_INTERNALS__;
+    var key = event.effectiveDirective + ' ' + event.blockedURI;
+    if (!internals || reported[key]) {
+      return;
+    }
+    reported[key] = true;
+    internals
+      .invoke('report_navigation_error', {
+        kind: 'csp',
+        url: event.blockedURI || event.documentURI,
+        detail: event.effectiveDirective || event.violatedDirective,
+      })
+      .catch(function () {});
+  });
+})();
@@ -0,0 +1,39 @@
+// Injects shell-configured headers into fetch() calls made to our own origins: the page
+// itself and the backend. The backend origin comes from the shell, which updates it if the
+// backend rebinds to another port; no other local service ever sees the headers.
+(function () {
+  if (window.__plutoRequestHeadersInstalled) {
+    return;
+  }
+  window.__plutoRequestHeadersInstalled = true;
+
+  var originalFetch = window.fetch.bind(window);
+  var internals = window.__TAURI_INTERNALS__;
+  var ready = internals
+    ? internals
+        .invoke('get_request_headers')
+        .then(function (config) {
+          window.__plutoRequestHeaders = Object.assign({}, config.headers, window.__plutoRequestHeaders);
+          window.__plutoBackendOrigin = window.__plutoBackendOrigin || config.backendOrigin;
+        })
+        .catch(function () {})
+    : Promise.resolve();
+
+  window.fetch = function (input, init) {
+    return ready.then(function () {
+      var headers = window.__plutoRequestHeaders || {};
+      var names = Object.keys(headers);
+      var target = new URL(input instanceof Request ? input.url : String(input), window.location.href);
+      var ownOrigin =
+        target.origin === window.location.origin || target.origin === window.__plutoBackendOrigin;
+      if (names.length === 0 || !ownOrigin) {
+        return originalFetch(input, init);
+      }
+      var request = new Request(input, init);
+      names.forEach(function (name) {
+        request.headers.set(name, headers[name]);
+      });
+      return originalFetch(request);
+    });
+  };
+})();
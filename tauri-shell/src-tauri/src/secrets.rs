@@ -0,0 +1,64 @@
+//! Hands sensitive values to the backend through a file only its user can read, instead of
+//! environment variables that show up in process listings. The backend loads the file into
+//! its own environment at startup and deletes it. Workers get the same secrets, each through a
+//! file of its own so one starting can't remove another's before it was read.
+
+use std::collections::HashMap;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+
+/// Hidden file in the data root; it only exists between spawn and the backend reading it.
+pub const SECRETS_FILE: &str = ".backend-secrets.json";
+/// Directory in the data root holding one such file per worker.
+pub const WORKER_SECRETS_DIR: &str = ".worker-secrets";
+/// Tells the backend (or a worker) where to find its file.
+pub const SECRETS_FILE_ENV: &str = "PLUTODUCK_SECRETS_FILE";
+
+/// Writes `secrets` as a JSON object of environment variable names to values, or removes a
+/// leftover file and returns `None` when there is nothing to pass.
+pub fn write_for_backend(
+  data_root: &Path,
+  secrets: &HashMap<String, String>,
+) -> Result<Option<PathBuf>> {
+  write(data_root.join(SECRETS_FILE), secrets)
+}
+
+/// Like [`write_for_backend`], for the worker called `name`.
+pub fn write_for_worker(
+  data_root: &Path,
+  name: &str,
+  secrets: &HashMap<String, String>,
+) -> Result<Option<PathBuf>> {
+  let dir = data_root.join(WORKER_SECRETS_DIR);
+  std::fs::create_dir_all(&dir).context("failed to create worker secrets directory")?;
+  write(dir.join(format!("{name}.json")), secrets)
+}
+
+fn write(path: PathBuf, secrets: &HashMap<String, String>) -> Result<Option<PathBuf>> {
+  // Recreated each time so the permissions below apply even if an old file was loosened.
+  match std::fs::remove_file(&path) {
+    Ok(()) => {}
+    Err(err) if err.kind() == std::io::ErrorKind::NotFound => {}
+    Err(err) => return Err(err).context("failed to remove stale secrets file"),
+  }
+  if secrets.is_empty() {
+    return Ok(None);
+  }
+
+  let mut options = std::fs::OpenOptions::new();
+  options.write(true).create_new(true);
+  // On Windows the data root under the user profile is already private to the user.
+  #[cfg(unix)]
+  {
+    use std::os::unix::fs::OpenOptionsExt;
+    options.mode(0o600);
+  }
+  let mut file = options
+    .open(&path)
+    .with_context(|| format!("failed to create {}", path.display()))?;
+  serde_json::to_writer(&mut file, secrets).context("failed to write secrets file")?;
+  file.flush()?;
+  Ok(Some(path))
+}
@@ -0,0 +1,124 @@
+//! Optional one-time setup step (`setup` in the env config) run before the backend starts,
+//! e.g. to migrate an old data layout on upgrade. The backend only starts once it exits
+//! successfully; `PLUTODUCK_SKIP_SETUP=1` skips it when troubleshooting.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::time::{Duration, Instant};
+
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use tauri::{AppHandle, Manager};
+
+use crate::config::{self, EnvConfig};
+use crate::{backend, logs, startup_trace};
+
+const SKIP_SETUP_ENV: &str = "PLUTODUCK_SKIP_SETUP";
+const DEFAULT_SETUP_TIMEOUT: Duration = Duration::from_secs(10 * 60);
+const SETUP_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+#[derive(Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SetupConfig {
+  /// Executable to run; relative paths resolve against the backend binary's directory.
+  pub command: PathBuf,
+  #[serde(default)]
+  pub args: Vec<String>,
+  #[serde(default)]
+  pub env: HashMap<String, String>,
+  /// How long the step may take before it is killed and counted as failed; ten minutes if
+  /// unset.
+  #[serde(default)]
+  pub timeout_secs: Option<u64>,
+}
+
+/// The setup step didn't complete, so the backend was not started. Shown to the user with
+/// where to find its output.
+#[derive(Debug)]
+pub struct SetupFailed {
+  pub command: PathBuf,
+  pub reason: String,
+  pub log: PathBuf,
+}
+
+impl std::fmt::Display for SetupFailed {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    write!(
+      f,
+      "Pluto Duck could not finish setting up before starting ({}: {}). Its output is in {}; set {SKIP_SETUP_ENV}=1 to start without it.",
+      self.command.display(),
+      self.reason,
+      self.log.display()
+    )
+  }
+}
+
+impl std::error::Error for SetupFailed {}
+
+/// Runs the configured setup step to completion, with its output in `setup.log` under the log
+/// directory. Does nothing when none is configured.
+pub fn run(app: &AppHandle) -> Result<()> {
+  let Some(setup) = app.state::<EnvConfig>().setup.clone() else {
+    return Ok(());
+  };
+  if config::env_flag(SKIP_SETUP_ENV) {
+    log::warn!("{SKIP_SETUP_ENV} set; skipping setup step {}", setup.command.display());
+    return Ok(());
+  }
+  let backend_binary = backend::backend_binary_path(app)?;
+  let backend_dir = backend_binary.parent().unwrap_or(Path::new(""));
+  let program = if setup.command.is_relative() {
+    backend_dir.join(&setup.command)
+  } else {
+    setup.command.clone()
+  };
+
+  let data_root = backend::resolve_data_root(app);
+  let log_dir = logs::log_dir(&data_root);
+  std::fs::create_dir_all(&log_dir).context("failed to create log directory")?;
+  let log_path = log_dir.join(logs::SETUP_LOG);
+  let stdout_log = logs::open_session_log(&log_path, logs::append_logs(app))
+    .context("failed to create setup log")?;
+  let stderr_log = stdout_log
+    .try_clone()
+    .context("failed to create setup log")?;
+  let failed = |reason: String| SetupFailed {
+    command: program.clone(),
+    reason,
+    log: log_path.clone(),
+  };
+
+  log::info!("running setup step {} {:?}", program.display(), setup.args);
+  startup_trace::record("setup step", || format!("{} {:?}", program.display(), setup.args));
+  let mut child = Command::new(&program)
+    .current_dir(backend_dir)
+    .envs(&app.state::<EnvConfig>().backend_env)
+    .envs(&setup.env)
+    .env("PLUTODUCK_DATA_DIR__ROOT", &data_root)
+    .args(&setup.args)
+    .stdin(Stdio::null())
+    .stdout(Stdio::from(stdout_log))
+    .stderr(Stdio::from(stderr_log))
+    .spawn()
+    .map_err(|err| failed(format!("could not start it: {err}")))?;
+
+  let timeout = setup.timeout_secs.map_or(DEFAULT_SETUP_TIMEOUT, Duration::from_secs);
+  let started = Instant::now();
+  let status = loop {
+    if let Some(status) = child.try_wait().context("failed to wait for setup step")? {
+      break status;
+    }
+    if started.elapsed() >= timeout {
+      let _ = child.kill();
+      let _ = child.wait();
+      return Err(failed(format!("still running after {timeout:?}")).into());
+    }
+    std::thread::sleep(SETUP_POLL_INTERVAL);
+  };
+  if !status.success() {
+    return Err(failed(format!("exited with {status}")).into());
+  }
+  log::info!("setup step finished in {:?}", started.elapsed());
+  Ok(())
+}
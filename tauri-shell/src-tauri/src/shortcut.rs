@@ -0,0 +1,77 @@
+use serde::Serialize;
+use tauri::{App, AppHandle, Emitter, Manager, Wry};
+use tauri_plugin_global_shortcut::{GlobalShortcut, GlobalShortcutExt, ShortcutState};
+
+use crate::preferences::{self, Preferences};
+
+const DEFAULT_TOGGLE_SHORTCUT: &str = "CmdOrCtrl+Shift+D";
+
+#[derive(Clone, Serialize)]
+struct ShortcutFailure {
+  shortcut: String,
+  error: String,
+}
+
+/// Registers the system-wide show/hide shortcut from preferences. A shortcut already claimed
+/// by another app is logged and reported with a `global-shortcut-failed` event, not fatal.
+pub fn install(app: &App) -> tauri::Result<()> {
+  app.handle().plugin(
+    tauri_plugin_global_shortcut::Builder::new()
+      .with_handler(|app, _shortcut, event| {
+        if event.state == ShortcutState::Pressed {
+          toggle_main_window(app);
+        }
+      })
+      .build(),
+  )?;
+  register_from_preferences(app.handle());
+  Ok(())
+}
+
+/// Replaces whatever shortcut is registered with the one in preferences; also used when
+/// preferences change underneath the running app.
+pub fn register_from_preferences(app: &AppHandle) {
+  if app.try_state::<GlobalShortcut<Wry>>().is_none() {
+    return;
+  }
+  if let Err(err) = app.global_shortcut().unregister_all() {
+    log::warn!("failed to unregister global shortcuts: {err}");
+  }
+  let shortcut = app
+    .state::<Preferences>()
+    .get(preferences::GLOBAL_SHORTCUT)
+    .and_then(|value| value.as_str().map(str::to_owned))
+    .unwrap_or_else(|| DEFAULT_TOGGLE_SHORTCUT.to_string());
+  if shortcut.trim().is_empty() {
+    log::info!("global shortcut disabled in preferences");
+    return;
+  }
+
+  if let Err(err) = app.global_shortcut().register(shortcut.as_str()) {
+    log::warn!("failed to register global shortcut {shortcut}: {err}");
+    let _ = app.emit(
+      "global-shortcut-failed",
+      ShortcutFailure {
+        shortcut,
+        error: err.to_string(),
+      },
+    );
+  } else {
+    log::info!("registered global shortcut {shortcut}");
+  }
+}
+
+fn toggle_main_window(app: &AppHandle) {
+  let Some(window) = app.get_webview_window("main") else {
+    crate::show_and_focus_main_window(app);
+    return;
+  };
+  let visible = window.is_visible().unwrap_or(false);
+  let focused = window.is_focused().unwrap_or(false);
+  if visible && focused {
+    let _ = window.hide();
+    crate::backend::stop_while_hidden(app);
+  } else {
+    crate::show_and_focus_main_window(app);
+  }
+}
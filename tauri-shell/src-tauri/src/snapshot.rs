@@ -0,0 +1,135 @@
+//! Everything support asks for in one JSON object, for the diagnostics bundle and the "Copy
+//! debug info" button. Each field comes from the helper behind its own query command, so the
+//! snapshot never disagrees with what those report.
+
+use serde::Serialize;
+use sysinfo::{Pid, ProcessRefreshKind, ProcessesToUpdate, System};
+use tauri::{AppHandle, Manager, WebviewWindow};
+
+use crate::backend::{self, BackendState};
+use crate::diagnostics::{self, BuildInfo, SystemInfo, Versions};
+use crate::status::{self, BackendStatus};
+use crate::window::{WindowPosition, WindowSize, WindowStateInfo};
+use crate::{crash, logs, navigation, readiness};
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WindowSnapshot {
+  pub label: String,
+  pub title: Option<String>,
+  pub url: Option<String>,
+  #[serde(flatten)]
+  pub state: WindowStateInfo,
+  pub position: Option<WindowPosition>,
+  pub size: Option<WindowSize>,
+  /// Display scaling. Page zoom isn't included: the shell never changes it and the webview
+  /// offers no way to read it back.
+  pub scale_factor: Option<f64>,
+  pub monitor: Option<String>,
+}
+
+impl WindowSnapshot {
+  fn of(window: &WebviewWindow) -> Self {
+    Self {
+      label: window.label().to_string(),
+      title: window.title().ok(),
+      url: window.url().ok().map(|url| url.to_string()),
+      state: WindowStateInfo::of(window),
+      position: window
+        .outer_position()
+        .ok()
+        .map(|position| WindowPosition {
+          x: position.x,
+          y: position.y,
+        }),
+      size: window.inner_size().ok().map(|size| WindowSize {
+        width: size.width,
+        height: size.height,
+      }),
+      scale_factor: window.scale_factor().ok(),
+      monitor: window
+        .current_monitor()
+        .ok()
+        .flatten()
+        .and_then(|monitor| monitor.name().cloned()),
+    }
+  }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BackendSnapshot {
+  pub status: BackendStatus,
+  pub pid: Option<u32>,
+  pub origin: String,
+  pub frontend_url: String,
+  pub memory_bytes: Option<u64>,
+  pub uptime_secs: Option<u64>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StateSnapshot {
+  /// RFC 3339.
+  pub taken_at: String,
+  pub versions: Versions,
+  pub build: BuildInfo,
+  pub system: SystemInfo,
+  pub startup_duration_ms: u64,
+  pub windows: Vec<WindowSnapshot>,
+  pub backend: BackendSnapshot,
+  pub stdout_tail: Vec<String>,
+  pub stderr_tail: Vec<String>,
+}
+
+fn backend_snapshot(app: &AppHandle) -> BackendSnapshot {
+  let pid = app
+    .try_state::<BackendState>()
+    .and_then(|state| backend::running_pid(&state).ok());
+  let process = pid.and_then(|pid| {
+    let pid = Pid::from_u32(pid);
+    let mut system = System::new();
+    system.refresh_processes_specifics(
+      ProcessesToUpdate::Some(&[pid]),
+      true,
+      ProcessRefreshKind::nothing().with_memory(),
+    );
+    system
+      .process(pid)
+      .map(|process| (process.memory(), process.run_time()))
+  });
+  BackendSnapshot {
+    status: status::backend_status(app.state()),
+    pid,
+    origin: backend::origin(),
+    frontend_url: navigation::effective_frontend_url(app.clone()),
+    memory_bytes: process.map(|(memory, _)| memory),
+    uptime_secs: process.map(|(_, uptime)| uptime),
+  }
+}
+
+/// Window geometry and state, the backend's status and resource use, versions and the tail of
+/// the backend logs, serialized as one object.
+#[tauri::command]
+pub async fn state_snapshot(app: AppHandle) -> Result<StateSnapshot, String> {
+  let versions = diagnostics::versions(app.clone()).await?;
+  let system = diagnostics::system_info().await;
+  let mut windows: Vec<WindowSnapshot> = app
+    .webview_windows()
+    .values()
+    .map(WindowSnapshot::of)
+    .collect();
+  windows.sort_by(|a, b| a.label.cmp(&b.label));
+  let log_dir = logs::log_dir(&backend::resolve_data_root(&app));
+  Ok(StateSnapshot {
+    taken_at: chrono::Utc::now().to_rfc3339(),
+    versions,
+    build: diagnostics::build_info(),
+    system,
+    startup_duration_ms: readiness::startup_duration_ms(),
+    windows,
+    backend: backend_snapshot(&app),
+    stdout_tail: crash::tail_lines(&log_dir.join(logs::STDOUT_LOG)),
+    stderr_tail: crash::tail_lines(&log_dir.join(logs::STDERR_LOG)),
+  })
+}
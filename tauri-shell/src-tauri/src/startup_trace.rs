@@ -0,0 +1,129 @@
+//! Opt-in, step-by-step record of a launch for support: with `PLUTODUCK_TRACE_STARTUP=1` every
+//! startup step (binary and data root resolution, the spawn, each health poll, navigations,
+//! window events) is written with a timestamp to `startup-trace.log` in the data root. Off by
+//! default, where recording a step costs one atomic load.
+
+use std::fs::File;
+use std::io::Write;
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+use crate::config;
+
+const TRACE_ENV: &str = "PLUTODUCK_TRACE_STARTUP";
+pub const TRACE_FILE: &str = "startup-trace.log";
+/// A launch that hasn't mounted by now is stuck; stop tracing so the file stays readable.
+const TRACE_LIMIT: Duration = Duration::from_secs(5 * 60);
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+static STARTED: OnceLock<Instant> = OnceLock::new();
+static SINK: Mutex<Sink> = Mutex::new(Sink::Buffered(Vec::new()));
+
+/// Steps recorded before the data root is known are buffered and written once it is.
+enum Sink {
+  Buffered(Vec<String>),
+  File(File),
+  Finished,
+}
+
+/// Called first thing in `run()`, right after the process start is recorded.
+pub fn init() {
+  if !config::env_flag(TRACE_ENV) {
+    return;
+  }
+  STARTED.get_or_init(Instant::now);
+  ENABLED.store(true, Ordering::SeqCst);
+  log::info!("{TRACE_ENV} set; tracing startup to {TRACE_FILE} in the data root");
+  record("process", || {
+    let args: Vec<String> = std::env::args().collect();
+    format!(
+      "pluto duck {} ({}/{}) args {args:?}",
+      env!("CARGO_PKG_VERSION"),
+      std::env::consts::OS,
+      std::env::consts::ARCH
+    )
+  });
+}
+
+pub fn enabled() -> bool {
+  ENABLED.load(Ordering::Relaxed)
+}
+
+/// Appends one step. `detail` is only evaluated while tracing.
+pub fn record(step: &str, detail: impl FnOnce() -> String) {
+  if !enabled() {
+    return;
+  }
+  let elapsed = STARTED.get().map(Instant::elapsed).unwrap_or_default();
+  if elapsed > TRACE_LIMIT {
+    finish("trace limit reached before the frontend mounted");
+    return;
+  }
+  let thread = std::thread::current();
+  let line = format!(
+    "+{:>7}ms {} [{}] {step}: {}\n",
+    elapsed.as_millis(),
+    chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Millis, true),
+    thread.name().unwrap_or("unnamed"),
+    detail()
+  );
+  let Ok(mut sink) = SINK.lock() else {
+    return;
+  };
+  match &mut *sink {
+    Sink::Buffered(lines) => lines.push(line),
+    Sink::File(file) => {
+      let _ = file.write_all(line.as_bytes());
+    }
+    Sink::Finished => {}
+  }
+}
+
+/// Starts the trace file in `data_root`, replacing the last one, with the steps so far.
+pub fn open(data_root: &Path) {
+  if !enabled() {
+    return;
+  }
+  let Ok(mut sink) = SINK.lock() else {
+    return;
+  };
+  let Sink::Buffered(lines) = &*sink else {
+    return;
+  };
+  let path = data_root.join(TRACE_FILE);
+  match File::create(&path) {
+    Ok(mut file) => {
+      for line in lines {
+        let _ = file.write_all(line.as_bytes());
+      }
+      *sink = Sink::File(file);
+    }
+    Err(err) => {
+      log::warn!("failed to create startup trace {}: {err}", path.display());
+      *sink = Sink::Finished;
+    }
+  }
+}
+
+/// Records the last step and stops tracing; later steps are not startup anymore.
+pub fn finish(reason: &str) {
+  if !enabled() {
+    return;
+  }
+  ENABLED.store(false, Ordering::SeqCst);
+  let Ok(mut sink) = SINK.lock() else {
+    return;
+  };
+  let elapsed = STARTED.get().map(Instant::elapsed).unwrap_or_default();
+  let line = format!("+{:>7}ms trace finished: {reason}\n", elapsed.as_millis());
+  match std::mem::replace(&mut *sink, Sink::Finished) {
+    Sink::File(mut file) => {
+      let _ = file.write_all(line.as_bytes());
+      let _ = file.flush();
+    }
+    Sink::Buffered(_) => log::warn!("startup trace finished before the data root was known"),
+    Sink::Finished => {}
+  }
+}
@@ -0,0 +1,129 @@
+//! Startup state reported by the backend's `/health`, so a long migration after an update
+//! shows up as such instead of a backend that never answers.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter, Manager, State};
+
+use crate::{api, backend, navigation, readiness, startup_trace, tasks};
+
+const STATUS_REQUEST_TIMEOUT: Duration = Duration::from_secs(2);
+/// Migrations on large databases can be slow; give up watching well after any sane one.
+const STATUS_WATCH_LIMIT: Duration = Duration::from_secs(30 * 60);
+
+/// Set by a restart so the next transition to ready reconnects the windows.
+static RECONNECT_PENDING: AtomicBool = AtomicBool::new(false);
+
+#[derive(Clone, PartialEq, Serialize)]
+pub struct BackendStatus {
+  /// `starting` until the backend answers, then its own state (`migrating`, `ready`, ...).
+  pub state: String,
+  pub message: Option<String>,
+}
+
+impl BackendStatus {
+  fn starting() -> Self {
+    Self {
+      state: "starting".to_string(),
+      message: None,
+    }
+  }
+
+  fn is_ready(&self) -> bool {
+    self.state == "ready"
+  }
+}
+
+pub struct BackendStatusState(Mutex<BackendStatus>);
+
+impl Default for BackendStatusState {
+  fn default() -> Self {
+    Self(Mutex::new(BackendStatus::starting()))
+  }
+}
+
+#[derive(Deserialize)]
+struct HealthPayload {
+  status: String,
+  message: Option<String>,
+}
+
+/// Polls `/health` after a (re)spawn and emits `backend-status-changed` on every transition
+/// until the backend reports ready, so the frontend can say "Migrating database…" meanwhile.
+pub fn watch(app: AppHandle) {
+  set(&app, BackendStatus::starting());
+  tasks::spawn("backend-status-watch", move || {
+    let started = Instant::now();
+    let mut backoff = readiness::PollBackoff::default();
+    while started.elapsed() < STATUS_WATCH_LIMIT {
+      if readiness::shutdown_requested() {
+        return;
+      }
+      let probed = tauri::async_runtime::block_on(probe());
+      startup_trace::record("health poll", || match &probed {
+        Some(status) => format!("{} {:?}", status.state, status.message),
+        None => "no answer".to_string(),
+      });
+      if let Some(status) = probed {
+        let ready = status.is_ready();
+        set(&app, status);
+        if ready {
+          if RECONNECT_PENDING.swap(false, Ordering::SeqCst) {
+            navigation::reconnect(&app);
+          }
+          return;
+        }
+      }
+      backoff.sleep();
+    }
+    log::warn!("backend did not report ready within {STATUS_WATCH_LIMIT:?}");
+  });
+}
+
+/// Marks (or, after a failed spawn, unmarks) the coming backend as a restart.
+pub fn reconnect_when_ready(pending: bool) {
+  RECONNECT_PENDING.store(pending, Ordering::SeqCst);
+}
+
+fn set(app: &AppHandle, status: BackendStatus) {
+  let state = app.state::<BackendStatusState>();
+  let Ok(mut current) = state.0.lock() else {
+    return;
+  };
+  if *current != status {
+    log::info!("backend status: {}", status.state);
+    *current = status.clone();
+    let _ = app.emit("backend-status-changed", status);
+  }
+}
+
+async fn probe() -> Option<BackendStatus> {
+  let response = api::client(STATUS_REQUEST_TIMEOUT)
+    .ok()?
+    .get(backend::health_url())
+    .send()
+    .await
+    .ok()?;
+  // A migrating backend may answer 503 with a status body; read it regardless of the code.
+  let payload: HealthPayload = response.json().await.ok()?;
+  let state = match payload.status.as_str() {
+    "ok" | "ready" => "ready".to_string(),
+    other => other.to_string(),
+  };
+  Some(BackendStatus {
+    state,
+    message: payload.message,
+  })
+}
+
+#[tauri::command]
+pub fn backend_status(state: State<'_, BackendStatusState>) -> BackendStatus {
+  state
+    .0
+    .lock()
+    .map(|status| status.clone())
+    .unwrap_or_else(|_| BackendStatus::starting())
+}
@@ -0,0 +1,146 @@
+//! Backend processes left running by earlier sessions, e.g. after the shell itself crashed.
+//! Each one keeps the DuckDB lock on the data root, so support needs a way to find and end
+//! them without walking the user through a task manager.
+
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+use serde::Serialize;
+use sysinfo::{Pid, ProcessRefreshKind, ProcessesToUpdate, System, UpdateKind};
+use tauri::{AppHandle, Manager};
+
+use crate::backend::{self, BackendState};
+use crate::{developer, process, workers};
+
+/// How long a stray gets to exit after SIGTERM before it is killed.
+const STRAY_EXIT_GRACE: Duration = Duration::from_secs(3);
+const STRAY_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StrayServer {
+  pub pid: u32,
+  pub exe: Option<PathBuf>,
+  pub command_line: String,
+  /// Seconds since the Unix epoch.
+  pub started_at: u64,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StrayKillFailure {
+  pub pid: u32,
+  pub error: String,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StrayKillResult {
+  /// Pids that are gone, whether they exited on SIGTERM, were killed or were already gone.
+  pub ended: Vec<u32>,
+  pub failed: Vec<StrayKillFailure>,
+}
+
+fn refresh(system: &mut System) {
+  system.refresh_processes_specifics(
+    ProcessesToUpdate::All,
+    true,
+    ProcessRefreshKind::nothing()
+      .with_cmd(UpdateKind::Always)
+      .with_exe(UpdateKind::Always),
+  );
+}
+
+/// Processes running our backend binary or started with our data root, minus this session's
+/// own: the shell, the current backend and workers, and anything they spawned (a one-file
+/// build's bootloader runs the real backend as its child).
+fn find(app: &AppHandle) -> Vec<StrayServer> {
+  let binary = backend::backend_binary_path(app).ok();
+  let data_root = backend::resolve_data_root(app);
+  let data_root = data_root.as_os_str();
+  let mut ours: Vec<u32> = vec![std::process::id()];
+  if let Some(state) = app.try_state::<BackendState>() {
+    ours.extend(backend::running_pid(&state).ok());
+  }
+  ours.extend(workers::pids(app));
+
+  let mut system = System::new();
+  refresh(&mut system);
+  system
+    .processes()
+    .iter()
+    .filter(|(pid, process)| {
+      let own = ours.contains(&pid.as_u32())
+        || process
+          .parent()
+          .is_some_and(|parent| ours.contains(&parent.as_u32()));
+      let runs_binary = binary.is_some() && process.exe() == binary.as_deref();
+      let uses_root = process.cmd().iter().any(|arg| arg == data_root);
+      !own && (runs_binary || uses_root)
+    })
+    .map(|(pid, process)| StrayServer {
+      pid: pid.as_u32(),
+      exe: process.exe().map(PathBuf::from),
+      command_line: process
+        .cmd()
+        .iter()
+        .map(|arg| arg.to_string_lossy())
+        .collect::<Vec<_>>()
+        .join(" "),
+      started_at: process.start_time(),
+    })
+    .collect()
+}
+
+/// Lists leftover backend processes from earlier sessions.
+#[tauri::command]
+pub async fn find_stray_servers(app: AppHandle) -> Result<Vec<StrayServer>, String> {
+  tauri::async_runtime::spawn_blocking(move || find(&app))
+    .await
+    .map_err(|err| err.to_string())
+}
+
+/// Ends every process [`find_stray_servers`] reports: SIGTERM first so DuckDB can close
+/// cleanly, then a kill for any still running after a few seconds. One that can't be killed
+/// doesn't stop the rest; the result lists which pids ended and which failed.
+#[tauri::command]
+pub async fn kill_stray_servers(app: AppHandle) -> Result<StrayKillResult, String> {
+  developer::ensure_enabled(&app)?;
+  tauri::async_runtime::spawn_blocking(move || {
+    let pids: Vec<u32> = find(&app).into_iter().map(|stray| stray.pid).collect();
+    for pid in &pids {
+      log::warn!("terminating stray backend process {pid}");
+      if let Err(err) = process::terminate(*pid) {
+        log::warn!("{err}");
+      }
+    }
+    let mut system = System::new();
+    let started = Instant::now();
+    let mut remaining = pids.clone();
+    while !remaining.is_empty() && started.elapsed() < STRAY_EXIT_GRACE {
+      std::thread::sleep(STRAY_POLL_INTERVAL);
+      refresh(&mut system);
+      remaining.retain(|pid| system.process(Pid::from_u32(*pid)).is_some());
+    }
+    let mut failed = Vec::new();
+    for pid in remaining {
+      log::warn!("stray backend process {pid} did not exit; killing it");
+      let Err(error) = process::kill(pid) else {
+        continue;
+      };
+      // It may have exited between the last poll and the kill, which is what we wanted.
+      refresh(&mut system);
+      if system.process(Pid::from_u32(pid)).is_some() {
+        log::warn!("{error}");
+        failed.push(StrayKillFailure { pid, error });
+      }
+    }
+    let ended = pids
+      .into_iter()
+      .filter(|pid| failed.iter().all(|failure| failure.pid != *pid))
+      .collect();
+    Ok(StrayKillResult { ended, failed })
+  })
+  .await
+  .map_err(|err| err.to_string())?
+}
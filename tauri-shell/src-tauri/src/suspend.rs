@@ -0,0 +1,150 @@
+//! Battery saver: with `suspend_when_hidden` on, the backend is paused (SIGSTOP) once every
+//! window has been hidden for `suspend_idle_secs` and continued as soon as one is shown. It is
+//! only paused while the frontend reports no work in flight, so DuckDB is never frozen halfway
+//! through a write while holding its lock. Unix only.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use serde_json::Value;
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::backend::{self, BackendState};
+use crate::preferences::{self, Preferences};
+use crate::{idle, process, readiness, tasks, window};
+
+const DEFAULT_SUSPEND_IDLE: Duration = Duration::from_secs(5 * 60);
+const SUSPEND_CHECK_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Bumped on every show (and every new schedule), so a pending suspend can tell it is stale.
+static HIDDEN_GENERATION: AtomicU64 = AtomicU64::new(0);
+/// Pid of the backend this module paused. Held while signalling so a show can't slip in
+/// between the staleness check and the SIGSTOP.
+static SUSPENDED: Mutex<Option<u32>> = Mutex::new(None);
+
+fn enabled(app: &AppHandle) -> bool {
+  cfg!(unix)
+    && app
+      .state::<Preferences>()
+      .get_bool(preferences::SUSPEND_WHEN_HIDDEN, false)
+}
+
+/// Starts the idle countdown once every window is hidden. Called on each hide; also when the
+/// frontend goes idle, since a busy frontend holds the countdown off.
+pub fn schedule(app: &AppHandle) {
+  if !enabled(app) || window::any_visible(app) {
+    return;
+  }
+  if SUSPENDED.lock().map_or(true, |suspended| suspended.is_some()) {
+    return;
+  }
+  let idle_for = app
+    .state::<Preferences>()
+    .get_u64(preferences::SUSPEND_IDLE_SECS)
+    .map_or(DEFAULT_SUSPEND_IDLE, Duration::from_secs);
+  let generation = HIDDEN_GENERATION.fetch_add(1, Ordering::SeqCst) + 1;
+  let app = app.clone();
+  tasks::spawn("suspend-while-hidden", move || {
+    let deadline = Instant::now() + idle_for;
+    loop {
+      if readiness::shutdown_requested() || HIDDEN_GENERATION.load(Ordering::SeqCst) != generation
+      {
+        return;
+      }
+      if Instant::now() >= deadline && !idle::is_busy() {
+        break;
+      }
+      std::thread::sleep(SUSPEND_CHECK_INTERVAL);
+    }
+    suspend(&app, generation);
+  });
+}
+
+/// The tracked backend pid. Read before taking [`SUSPENDED`], never while holding it: respawns
+/// take `SUSPENDED` (through [`backend_replaced`]) while holding the backend state.
+fn tracked_pid(app: &AppHandle) -> Option<u32> {
+  app
+    .try_state::<BackendState>()
+    .and_then(|state| backend::running_pid(&state).ok())
+}
+
+fn suspend(app: &AppHandle, generation: u64) {
+  let Some(pid) = tracked_pid(app) else {
+    return;
+  };
+  let Ok(mut suspended) = SUSPENDED.lock() else {
+    return;
+  };
+  // A replacement since the pid was read bumps the generation, so `pid` can't be stale here.
+  if HIDDEN_GENERATION.load(Ordering::SeqCst) != generation || suspended.is_some() {
+    return;
+  }
+  if let Err(err) = process::suspend(pid) {
+    log::warn!("could not suspend backend while hidden: {err}");
+    return;
+  }
+  log::info!("all windows hidden and idle; backend process {pid} suspended");
+  *suspended = Some(pid);
+  let _ = app.emit("backend-suspended", pid);
+}
+
+/// Forgets a paused backend that has been replaced, so a stale pid doesn't hold off the
+/// countdown for its successor, which starts counting again if every window is still hidden.
+/// Called whenever a new backend process is spawned, with the backend state locked.
+pub fn backend_replaced(app: &AppHandle) {
+  HIDDEN_GENERATION.fetch_add(1, Ordering::SeqCst);
+  if let Ok(mut suspended) = SUSPENDED.lock() {
+    if let Some(pid) = suspended.take() {
+      log::info!("suspended backend process {pid} was replaced");
+    }
+  }
+  schedule(app);
+}
+
+/// Continues a backend paused by [`schedule`] and cancels any pending countdown. Called on
+/// every show and whenever the frontend starts work.
+pub fn resume(app: &AppHandle) {
+  HIDDEN_GENERATION.fetch_add(1, Ordering::SeqCst);
+  let tracked = tracked_pid(app);
+  let Ok(mut suspended) = SUSPENDED.lock() else {
+    return;
+  };
+  let Some(pid) = suspended.take() else {
+    return;
+  };
+  // A backend restarted meanwhile was continued by its stop; the pid may be reused by now.
+  if tracked != Some(pid) {
+    return;
+  }
+  match process::resume(pid) {
+    Ok(()) => {
+      log::info!("backend process {pid} resumed");
+      let _ = app.emit("backend-resumed", pid);
+    }
+    Err(err) => log::warn!("could not resume suspended backend: {err}"),
+  }
+}
+
+/// Turns suspending the backend while hidden on or off, optionally with a new idle period.
+#[tauri::command]
+pub fn set_suspend_when_hidden(
+  app: AppHandle,
+  enabled: bool,
+  idle_secs: Option<u64>,
+) -> Result<(), String> {
+  if enabled && !cfg!(unix) {
+    return Err("Suspending the backend is not supported on this platform".to_string());
+  }
+  let preferences = app.state::<Preferences>();
+  preferences.set(preferences::SUSPEND_WHEN_HIDDEN, Value::Bool(enabled))?;
+  if let Some(idle_secs) = idle_secs {
+    preferences.set(preferences::SUSPEND_IDLE_SECS, Value::from(idle_secs))?;
+  }
+  if enabled {
+    schedule(&app);
+  } else {
+    resume(&app);
+  }
+  Ok(())
+}
@@ -0,0 +1,106 @@
+//! Registry of the shell's background threads, so a debug build or developer mode can check
+//! that e.g. log forwarding is still alive.
+
+use std::sync::atomic::{AtomicU8, Ordering};
+use std::sync::{Arc, Mutex};
+
+use serde::Serialize;
+use tauri::AppHandle;
+
+use crate::developer;
+
+/// Finished tasks kept for inspection; older ones are dropped as new tasks start.
+const MAX_FINISHED_TASKS: usize = 32;
+
+const ALIVE: u8 = 0;
+const DEAD: u8 = 1;
+const PANICKED: u8 = 2;
+
+struct TaskEntry {
+  name: String,
+  started_at: String,
+  status: Arc<AtomicU8>,
+}
+
+/// Process-wide rather than managed state so threads spawned before or outside an
+/// `AppHandle` (and by code holding only a window) register the same way.
+static REGISTRY: Mutex<Vec<TaskEntry>> = Mutex::new(Vec::new());
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TaskInfo {
+  name: String,
+  /// `alive`, `dead` (returned normally) or `panicked`.
+  status: &'static str,
+  started_at: String,
+}
+
+/// Marks the task dead when its thread ends, including by panic.
+struct ExitGuard(Arc<AtomicU8>);
+
+impl Drop for ExitGuard {
+  fn drop(&mut self) {
+    let status = if std::thread::panicking() { PANICKED } else { DEAD };
+    self.0.store(status, Ordering::SeqCst);
+  }
+}
+
+fn register(name: &str) -> Arc<AtomicU8> {
+  let status = Arc::new(AtomicU8::new(ALIVE));
+  if let Ok(mut tasks) = REGISTRY.lock() {
+    let finished = tasks
+      .iter()
+      .filter(|task| task.status.load(Ordering::SeqCst) != ALIVE)
+      .count();
+    if finished >= MAX_FINISHED_TASKS {
+      if let Some(oldest) = tasks
+        .iter()
+        .position(|task| task.status.load(Ordering::SeqCst) != ALIVE)
+      {
+        tasks.remove(oldest);
+      }
+    }
+    tasks.push(TaskEntry {
+      name: name.to_string(),
+      started_at: chrono::Utc::now().to_rfc3339(),
+      status: status.clone(),
+    });
+  }
+  status
+}
+
+/// Spawns a named, tracked thread.
+pub fn spawn(name: &str, task: impl FnOnce() + Send + 'static) {
+  let status = register(name);
+  let tracked = status.clone();
+  let spawned = std::thread::Builder::new()
+    .name(name.to_string())
+    .spawn(move || {
+      let _guard = ExitGuard(tracked);
+      task();
+    });
+  if let Err(err) = spawned {
+    log::error!("failed to spawn background task {name}: {err}");
+    status.store(DEAD, Ordering::SeqCst);
+  }
+}
+
+#[tauri::command]
+pub fn list_background_tasks(app: AppHandle) -> Result<Vec<TaskInfo>, String> {
+  developer::ensure_enabled(&app)?;
+  let tasks = REGISTRY.lock().map_err(|err| err.to_string())?;
+  Ok(
+    tasks
+      .iter()
+      .map(|task| TaskInfo {
+        name: task.name.clone(),
+        status: match task.status.load(Ordering::SeqCst) {
+          ALIVE => "alive",
+          PANICKED => "panicked",
+          _ => "dead",
+        },
+        started_at: task.started_at.clone(),
+      })
+      .collect(),
+  )
+}
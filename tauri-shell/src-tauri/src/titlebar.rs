@@ -0,0 +1,241 @@
+//! macOS titlebar chrome. Two approaches give the overlay titlebar our height: a transparent
+//! titlebar accessory view (the default) or a unified compact toolbar; `native` drops the
+//! overlay for the standard titlebar. Other platforms keep their native decorations.
+
+use tauri::WebviewWindow;
+
+#[cfg(target_os = "macos")]
+use crate::tasks;
+
+#[cfg(target_os = "macos")]
+const TITLEBAR_RETRY_DELAY: std::time::Duration = std::time::Duration::from_millis(100);
+#[cfg(target_os = "macos")]
+const TITLEBAR_HEIGHT: f64 = 40.0;
+
+#[derive(Clone, Copy, Debug)]
+pub enum TitlebarMode {
+  Accessory,
+  Unified,
+  Native,
+}
+
+impl TitlebarMode {
+  fn parse(mode: &str) -> Result<Self, String> {
+    match mode.trim().to_ascii_lowercase().as_str() {
+      "accessory" => Ok(Self::Accessory),
+      "unified" => Ok(Self::Unified),
+      "native" => Ok(Self::Native),
+      other => Err(format!(
+        "Unknown titlebar mode {other:?}; expected accessory, unified or native"
+      )),
+    }
+  }
+}
+
+/// The window's NSWindow, or `None` with a warning naming what couldn't be applied.
+#[cfg(target_os = "macos")]
+pub fn ns_window_for(window: &WebviewWindow, purpose: &str) -> Option<cocoa::base::id> {
+  match window.ns_window() {
+    Ok(ns_window) => Some(ns_window as cocoa::base::id),
+    Err(err) => {
+      log::warn!("NSWindow unavailable; skipping {purpose}: {err}");
+      None
+    }
+  }
+}
+
+/// Transparent, title-less titlebar with our height. The NSWindow can be briefly unavailable
+/// right after creation, so the first attempt retries once from a short delay.
+#[cfg(target_os = "macos")]
+pub fn apply_titlebar(window: &WebviewWindow, retry: bool) {
+  if retry && window.ns_window().is_err() {
+    log::warn!("NSWindow not ready; retrying titlebar setup in {TITLEBAR_RETRY_DELAY:?}");
+    let window = window.clone();
+    tasks::spawn("titlebar-retry", move || {
+      std::thread::sleep(TITLEBAR_RETRY_DELAY);
+      let target = window.clone();
+      if let Err(err) = window.run_on_main_thread(move || apply_titlebar(&target, false)) {
+        log::warn!("failed to schedule titlebar setup retry: {err}");
+      }
+    });
+    return;
+  }
+
+  apply_transparent_titlebar(window, true);
+  // Ensure the system knows our desired titlebar height without per-resize tweaking
+  apply_titlebar_accessory(window, TITLEBAR_HEIGHT);
+}
+
+/// Switches titlebar approach at runtime, removing the other approach's accessory views or
+/// toolbar first so they never stack.
+#[tauri::command]
+pub fn set_titlebar_mode(window: WebviewWindow, mode: String) -> Result<(), String> {
+  let mode = TitlebarMode::parse(&mode)?;
+  #[cfg(target_os = "macos")]
+  {
+    let target = window.clone();
+    window
+      .run_on_main_thread(move || apply_mode(&target, mode))
+      .map_err(|err| err.to_string())
+  }
+
+  #[cfg(not(target_os = "macos"))]
+  {
+    let _ = (window, mode);
+    Err("Titlebar modes are only available on macOS".to_string())
+  }
+}
+
+#[cfg(target_os = "macos")]
+fn apply_mode(window: &WebviewWindow, mode: TitlebarMode) {
+  use tauri::TitleBarStyle;
+
+  log::info!("switching titlebar mode to {mode:?}");
+  remove_titlebar_accessories(window);
+  remove_toolbar(window);
+  let style = match mode {
+    TitlebarMode::Native => TitleBarStyle::Visible,
+    TitlebarMode::Accessory | TitlebarMode::Unified => TitleBarStyle::Overlay,
+  };
+  if let Err(err) = window.set_title_bar_style(style) {
+    log::warn!("failed to set titlebar style: {err}");
+  }
+  match mode {
+    TitlebarMode::Accessory => {
+      apply_transparent_titlebar(window, true);
+      apply_titlebar_accessory(window, TITLEBAR_HEIGHT);
+    }
+    TitlebarMode::Unified => {
+      apply_transparent_titlebar(window, true);
+      apply_unified_toolbar(window);
+    }
+    TitlebarMode::Native => apply_transparent_titlebar(window, false),
+  }
+  crate::appearance::restore_accent_color(window);
+}
+
+#[cfg(target_os = "macos")]
+fn apply_transparent_titlebar(window: &WebviewWindow, transparent: bool) {
+  use cocoa::appkit::{NSColor, NSWindow, NSWindowTitleVisibility};
+  use cocoa::base::{id, nil, NO, YES};
+  use objc::{class, msg_send, sel, sel_impl};
+
+  let Some(ns_window) = ns_window_for(window, "titlebar transparency") else {
+    return;
+  };
+  unsafe {
+    if transparent {
+      ns_window.setTitlebarAppearsTransparent_(YES);
+      ns_window.setOpaque_(NO);
+      ns_window.setBackgroundColor_(NSColor::clearColor(nil));
+      ns_window.setTitleVisibility_(NSWindowTitleVisibility::NSWindowTitleHidden);
+    } else {
+      let background: id = msg_send![class!(NSColor), windowBackgroundColor];
+      ns_window.setTitlebarAppearsTransparent_(NO);
+      ns_window.setOpaque_(YES);
+      ns_window.setBackgroundColor_(background);
+      ns_window.setTitleVisibility_(NSWindowTitleVisibility::NSWindowTitleVisible);
+    }
+  }
+}
+
+#[cfg(target_os = "macos")]
+fn remove_titlebar_accessories(window: &WebviewWindow) {
+  use cocoa::base::id;
+  use objc::{msg_send, sel, sel_impl};
+
+  if let Some(ns_window) = ns_window_for(window, "removing titlebar accessories") {
+    unsafe {
+      let controllers: id = msg_send![ns_window, titlebarAccessoryViewControllers];
+      let count: usize = msg_send![controllers, count];
+      for index in (0..count).rev() {
+        let index = index as isize;
+        let _: () = msg_send![ns_window, removeTitlebarAccessoryViewControllerAtIndex: index];
+      }
+    }
+  }
+}
+
+#[cfg(target_os = "macos")]
+fn remove_toolbar(window: &WebviewWindow) {
+  use cocoa::base::nil;
+  use objc::{msg_send, sel, sel_impl};
+
+  if let Some(ns_window) = ns_window_for(window, "removing toolbar") {
+    unsafe {
+      let _: () = msg_send![ns_window, setToolbar: nil];
+    }
+  }
+}
+
+#[cfg(target_os = "macos")]
+fn apply_titlebar_accessory(window: &WebviewWindow, height: f64) {
+  use cocoa::appkit::NSView;
+  use cocoa::base::{id, nil, YES};
+  use cocoa::foundation::{NSPoint, NSRect, NSSize};
+  use objc::{class, msg_send, sel, sel_impl};
+
+  if let Some(ns_window) = ns_window_for(window, "titlebar accessory") {
+    unsafe {
+      let accessory: id = msg_send![class!(NSTitlebarAccessoryViewController), new];
+      let view: id = NSView::alloc(nil).initWithFrame_(NSRect::new(
+        NSPoint::new(0.0, 0.0),
+        NSSize::new(1.0, height),
+      ));
+      let _: () = msg_send![view, setWantsLayer: YES];
+      // Transparent accessory; only height matters for layout
+      let _: () = msg_send![view, setAlphaValue: 0.0f64];
+
+      let _: () = msg_send![accessory, setView: view];
+      // Add accessory so AppKit derives titlebar height from its view
+      let _: () = msg_send![ns_window, addTitlebarAccessoryViewController: accessory];
+    }
+  }
+}
+
+#[cfg(target_os = "macos")]
+fn apply_unified_toolbar(window: &WebviewWindow) {
+  use cocoa::base::{id, nil, NO, YES, BOOL};
+  use cocoa::foundation::NSString;
+  use objc::{class, msg_send, sel, sel_impl};
+
+  if let Some(ns_window) = ns_window_for(window, "unified toolbar") {
+    unsafe {
+      // Create NSToolbar with an identifier
+      let identifier = NSString::alloc(nil).init_str("PlutoDuckToolbar");
+      let toolbar: id = msg_send![class!(NSToolbar), alloc];
+      let toolbar: id = msg_send![toolbar, initWithIdentifier: identifier];
+
+      // Optional cosmetic adjustments
+      let _: () = msg_send![toolbar, setShowsBaselineSeparator: NO];
+      // Small size mode (1). Default is 0. This helps lower the baseline.
+      let _: () = msg_send![toolbar, setSizeMode: 1u64];
+
+      // Attach toolbar to window
+      let _: () = msg_send![ns_window, setToolbar: toolbar];
+
+      // Try to center/compact further by setting toolbar style when available.
+      // We avoid hardcoding NSWindowToolbarStyle enums to keep compatibility.
+      // If the selector exists, set to UnifiedCompact (commonly = 5) as a best-effort.
+      let sel_toolbarStyle = sel!(setToolbarStyle:);
+      let responds: BOOL = msg_send![ns_window, respondsToSelector: sel_toolbarStyle];
+      if responds == YES {
+        let unified_compact: u64 = 8; // NSWindowToolbarStyleUnifiedCompact (best-effort)
+        let _: () = msg_send![ns_window, setToolbarStyle: unified_compact];
+      }
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn parses_titlebar_modes_case_insensitively() {
+    assert!(matches!(TitlebarMode::parse("accessory"), Ok(TitlebarMode::Accessory)));
+    assert!(matches!(TitlebarMode::parse(" Unified "), Ok(TitlebarMode::Unified)));
+    assert!(matches!(TitlebarMode::parse("NATIVE"), Ok(TitlebarMode::Native)));
+    assert!(TitlebarMode::parse("overlay").is_err());
+  }
+}
@@ -0,0 +1,154 @@
+//! Opt-in local HTTPS (`PLUTODUCK_FRONTEND_TLS=1`) for frontend features that need a secure
+//! context, such as WebAuthn. The shell mints a throwaway CA and a `localhost`/`127.0.0.1`
+//! certificate signed by it under the data root, hands the pair to the backend, trusts the CA
+//! in its own HTTP client, and lets the webview accept the certificate for loopback only.
+//!
+//! The CA's private key is never written down, so nothing else can be signed with it.
+
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+use anyhow::{Context, Result};
+use rcgen::{BasicConstraints, CertificateParams, DnType, IsCa, Issuer, KeyPair, KeyUsagePurpose};
+
+use crate::config;
+
+const TLS_ENV: &str = "PLUTODUCK_FRONTEND_TLS";
+pub const TLS_DIR: &str = "tls";
+const CA_FILE: &str = "ca.pem";
+const CERT_FILE: &str = "cert.pem";
+const KEY_FILE: &str = "key.pem";
+const LOOPBACK_NAMES: [&str; 2] = ["localhost", "127.0.0.1"];
+/// WebView2 ignores certificate errors for loopback hosts only. wry's own defaults are repeated
+/// because setting browser arguments replaces them.
+#[cfg(windows)]
+pub const WEBVIEW2_ARGS: &str =
+  "--disable-features=msWebOOUI,msPdfOOUI,msSmartScreenProtection --allow-insecure-localhost";
+
+static CERTS: OnceLock<LocalCert> = OnceLock::new();
+
+#[derive(Clone)]
+pub struct LocalCert {
+  pub ca: PathBuf,
+  pub cert: PathBuf,
+  pub key: PathBuf,
+}
+
+pub fn enabled() -> bool {
+  static ENABLED: OnceLock<bool> = OnceLock::new();
+  *ENABLED.get_or_init(|| config::env_flag(TLS_ENV))
+}
+
+pub fn scheme() -> &'static str {
+  if enabled() {
+    "https"
+  } else {
+    "http"
+  }
+}
+
+/// The certificate for the backend, generated on first use and reused after that.
+pub fn ensure_cert(data_root: &Path) -> Result<LocalCert> {
+  if let Some(certs) = CERTS.get() {
+    return Ok(certs.clone());
+  }
+  let dir = data_root.join(TLS_DIR);
+  let certs = LocalCert {
+    ca: dir.join(CA_FILE),
+    cert: dir.join(CERT_FILE),
+    key: dir.join(KEY_FILE),
+  };
+  if !(certs.ca.is_file() && certs.cert.is_file() && certs.key.is_file()) {
+    log::info!("generating local TLS certificate in {}", dir.display());
+    std::fs::create_dir_all(&dir).context("failed to create TLS directory")?;
+    generate(&certs)?;
+  }
+  Ok(CERTS.get_or_init(|| certs).clone())
+}
+
+fn generate(certs: &LocalCert) -> Result<()> {
+  let mut ca_params = CertificateParams::new(Vec::<String>::new())?;
+  ca_params.is_ca = IsCa::Ca(BasicConstraints::Constrained(0));
+  ca_params.key_usages = vec![KeyUsagePurpose::KeyCertSign];
+  ca_params
+    .distinguished_name
+    .push(DnType::CommonName, "Pluto Duck local CA");
+  let ca_key = KeyPair::generate()?;
+  let ca_cert = ca_params.self_signed(&ca_key)?;
+  let issuer = Issuer::new(ca_params, ca_key);
+
+  let names: Vec<String> = LOOPBACK_NAMES.iter().map(|name| name.to_string()).collect();
+  let mut params = CertificateParams::new(names)?;
+  params.distinguished_name.push(DnType::CommonName, "localhost");
+  let key = KeyPair::generate()?;
+  let cert = params.signed_by(&key, &issuer)?;
+
+  std::fs::write(&certs.ca, ca_cert.pem()).context("failed to write TLS CA")?;
+  std::fs::write(&certs.cert, cert.pem()).context("failed to write TLS certificate")?;
+  std::fs::write(&certs.key, key.serialize_pem()).context("failed to write TLS key")?;
+  #[cfg(unix)]
+  {
+    use std::os::unix::fs::PermissionsExt;
+    let _ = std::fs::set_permissions(&certs.key, std::fs::Permissions::from_mode(0o600));
+  }
+  Ok(())
+}
+
+/// The local CA for the shell's own requests to the backend, once a certificate exists.
+pub fn root_certificate() -> Option<reqwest::Certificate> {
+  let certs = CERTS.get()?;
+  let pem = std::fs::read(&certs.ca)
+    .map_err(|err| log::warn!("failed to read TLS CA {}: {err}", certs.ca.display()))
+    .ok()?;
+  reqwest::Certificate::from_pem(&pem)
+    .map_err(|err| log::warn!("invalid TLS CA {}: {err}", certs.ca.display()))
+    .ok()
+}
+
+/// Lets the window's webview accept the backend's certificate for the loopback hosts. Only
+/// WebKitGTK needs this per window; WebView2 gets [`WEBVIEW2_ARGS`] at creation and WKWebView
+/// offers no way to do it, so macOS users need the CA trusted in their keychain.
+pub fn trust_in_webview(window: &tauri::WebviewWindow) {
+  if !enabled() {
+    return;
+  }
+  let Some(certs) = CERTS.get().cloned() else {
+    return;
+  };
+  #[cfg(target_os = "linux")]
+  {
+    let trusted = window.with_webview(move |webview| {
+      use webkit2gtk::gio::TlsCertificate;
+      use webkit2gtk::{WebContextExt, WebViewExt};
+
+      let certificate = match TlsCertificate::from_file(&certs.cert) {
+        Ok(certificate) => certificate,
+        Err(err) => {
+          log::warn!("failed to load TLS certificate for the webview: {err}");
+          return;
+        }
+      };
+      if let Some(context) = webview.inner().context() {
+        for host in LOOPBACK_NAMES {
+          context.allow_tls_certificate_for_host(&certificate, host);
+        }
+      }
+    });
+    if let Err(err) = trusted {
+      log::warn!("failed to trust the local TLS certificate in the webview: {err}");
+    }
+  }
+  #[cfg(target_os = "macos")]
+  {
+    let _ = window;
+    log::warn!(
+      "{TLS_ENV} is set but WKWebView can't be told to accept the local certificate; trust {} \
+       in Keychain Access",
+      certs.ca.display()
+    );
+  }
+  #[cfg(windows)]
+  {
+    let _ = (window, certs);
+  }
+}
@@ -0,0 +1,71 @@
+use tauri::menu::{Menu, MenuBuilder, MenuItemBuilder, SubmenuBuilder};
+use tauri::tray::{MouseButton, MouseButtonState, TrayIconBuilder, TrayIconEvent};
+use tauri::{App, AppHandle, Wry};
+
+use crate::recent::{self, RecentItem};
+
+const TRAY_ID: &str = "main";
+const MENU_SHOW: &str = "tray-show";
+const MENU_QUIT: &str = "tray-quit";
+const MENU_RECENT_PREFIX: &str = "tray-recent:";
+
+/// Installs the tray icon that brings the main window back after it was hidden
+/// by hide-on-close or minimize-to-tray.
+pub fn install(app: &App) -> tauri::Result<()> {
+  let menu = build_menu(app.handle(), &[])?;
+
+  let mut builder = TrayIconBuilder::with_id(TRAY_ID)
+    .tooltip("Pluto Duck")
+    .menu(&menu)
+    .show_menu_on_left_click(false)
+    .on_menu_event(|app, event| match event.id().as_ref() {
+      MENU_SHOW => {
+        crate::show_and_focus_main_window(app);
+      }
+      MENU_QUIT => app.exit(0),
+      id => {
+        if let Some(recent_id) = id.strip_prefix(MENU_RECENT_PREFIX) {
+          recent::open(app, recent_id);
+        }
+      }
+    })
+    .on_tray_icon_event(|tray, event| {
+      if let TrayIconEvent::Click {
+        button: MouseButton::Left,
+        button_state: MouseButtonState::Up,
+        ..
+      } = event
+      {
+        crate::show_and_focus_main_window(tray.app_handle());
+      }
+    });
+  if let Some(icon) = app.default_window_icon() {
+    builder = builder.icon(icon.clone());
+  }
+  builder.build(app)?;
+  Ok(())
+}
+
+/// Rebuilds the tray menu with an "Open Recent" submenu; an empty list drops the submenu.
+pub fn set_recent_items(app: &AppHandle, items: &[RecentItem]) -> tauri::Result<()> {
+  let Some(tray) = app.tray_by_id(TRAY_ID) else {
+    return Ok(());
+  };
+  tray.set_menu(Some(build_menu(app, items)?))
+}
+
+fn build_menu(app: &AppHandle, recent_items: &[RecentItem]) -> tauri::Result<Menu<Wry>> {
+  let show = MenuItemBuilder::with_id(MENU_SHOW, "Show Pluto Duck").build(app)?;
+  let quit = MenuItemBuilder::with_id(MENU_QUIT, "Quit Pluto Duck").build(app)?;
+  let mut menu = MenuBuilder::new(app).item(&show);
+  if !recent_items.is_empty() {
+    let mut submenu = SubmenuBuilder::new(app, "Open Recent");
+    for item in recent_items {
+      submenu = submenu.item(
+        &MenuItemBuilder::with_id(format!("{MENU_RECENT_PREFIX}{}", item.id), &item.name).build(app)?,
+      );
+    }
+    menu = menu.item(&submenu.build()?);
+  }
+  menu.item(&quit).build()
+}
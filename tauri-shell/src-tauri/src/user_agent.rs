@@ -0,0 +1,61 @@
+use serde::Serialize;
+use serde_json::Value;
+use tauri::{AppHandle, Manager, State, WebviewWindow};
+
+use crate::developer;
+use crate::preferences::{self, Preferences};
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UserAgentInfo {
+  pub default: String,
+  pub effective: String,
+}
+
+/// `PlutoDuck/<version> (<platform>)`, so the backend can tell shell requests from a browser.
+pub fn default_user_agent(app: &AppHandle) -> String {
+  let platform = match std::env::consts::OS {
+    "macos" => "macOS",
+    "windows" => "Windows",
+    "linux" => "Linux",
+    other => other,
+  };
+  format!("PlutoDuck/{} ({platform})", app.package_info().version)
+}
+
+/// The developer override from preferences if one is set, otherwise the default.
+pub fn effective_user_agent(app: &AppHandle) -> String {
+  app
+    .state::<Preferences>()
+    .get(preferences::USER_AGENT_OVERRIDE)
+    .and_then(|value| value.as_str().map(str::to_owned))
+    .filter(|value| !value.trim().is_empty())
+    .unwrap_or_else(|| default_user_agent(app))
+}
+
+#[tauri::command]
+pub fn get_user_agent(app: AppHandle) -> UserAgentInfo {
+  UserAgentInfo {
+    default: default_user_agent(&app),
+    effective: effective_user_agent(&app),
+  }
+}
+
+/// Stores a developer override for the webview User-Agent, or clears it with `null`. Webviews
+/// can't change it after creation, so it applies from the next launch.
+#[tauri::command]
+pub fn set_user_agent(
+  window: WebviewWindow,
+  preferences: State<'_, Preferences>,
+  ua: Option<String>,
+) -> Result<(), String> {
+  developer::ensure_enabled(window.app_handle())?;
+  let value = match ua {
+    Some(ua) if ua.chars().any(char::is_control) => {
+      return Err("User-Agent must not contain control characters".to_string());
+    }
+    Some(ua) => Value::String(ua),
+    None => Value::Null,
+  };
+  preferences.set(preferences::USER_AGENT_OVERRIDE, value)
+}
@@ -0,0 +1,115 @@
+//! Keeps the backend from failing in confusing ways when the drive holding the data root goes
+//! away mid-session (an external disk unplugged, a network share dropping): the backend is
+//! paused and `data-volume-lost` asks the user to reconnect; `data-volume-restored` follows
+//! once the same volume is back.
+
+use std::path::{Path, PathBuf};
+use std::process::Child;
+use std::time::Duration;
+
+use log::{info, warn};
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::backend::BackendState;
+use crate::{process, readiness, tasks};
+
+const VOLUME_WATCH_INTERVAL: Duration = Duration::from_secs(2);
+
+#[derive(Clone, Serialize)]
+struct DataVolumeEvent {
+  path: PathBuf,
+}
+
+/// Launch failure because the data root can't be reached at all, typically because the drive
+/// it lives on isn't connected.
+#[derive(Debug)]
+pub struct DataVolumeMissing {
+  pub path: PathBuf,
+}
+
+impl std::fmt::Display for DataVolumeMissing {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    write!(
+      f,
+      "Pluto Duck's data folder {} isn't available. If it is on an external or network drive, reconnect it and start Pluto Duck again.",
+      self.path.display()
+    )
+  }
+}
+
+impl std::error::Error for DataVolumeMissing {}
+
+/// Identifies the filesystem holding `path`, or `None` if the path can't be reached. On unix
+/// an unmounted drive usually leaves its empty mount point behind, so the device id tells the
+/// two apart; elsewhere a missing drive makes the path itself unreachable.
+fn volume_of(path: &Path) -> Option<u64> {
+  let metadata = std::fs::metadata(path).ok()?;
+  if !metadata.is_dir() {
+    return None;
+  }
+  #[cfg(unix)]
+  {
+    use std::os::unix::fs::MetadataExt;
+    Some(metadata.dev())
+  }
+  #[cfg(not(unix))]
+  Some(0)
+}
+
+pub fn verify_present(data_root: &Path) -> Result<(), DataVolumeMissing> {
+  match volume_of(data_root) {
+    Some(_) => Ok(()),
+    None => Err(DataVolumeMissing {
+      path: data_root.to_path_buf(),
+    }),
+  }
+}
+
+/// Watches the data root's volume for as long as the backend with `pid` is the tracked one.
+pub fn watch(app: AppHandle, pid: u32, data_root: PathBuf) {
+  let Some(volume) = volume_of(&data_root) else {
+    return;
+  };
+  tasks::spawn("data-volume-watch", move || {
+    let mut lost = false;
+    loop {
+      std::thread::sleep(VOLUME_WATCH_INTERVAL);
+      // A replacement backend has its own watch; a paused one is continued by its stop.
+      if readiness::shutdown_requested() || !is_tracked(&app, pid) {
+        return;
+      }
+      let present = volume_of(&data_root) == Some(volume);
+      if lost != present {
+        continue;
+      }
+      lost = !present;
+      let payload = DataVolumeEvent {
+        path: data_root.clone(),
+      };
+      if lost {
+        warn!("data volume for {} disappeared; pausing backend", data_root.display());
+        if let Err(err) = process::suspend(pid) {
+          warn!("could not pause backend while its data volume is gone: {err}");
+        }
+        let _ = app.emit("data-volume-lost", payload);
+      } else {
+        info!("data volume for {} is back; resuming backend", data_root.display());
+        if let Err(err) = process::resume(pid) {
+          warn!("could not resume backend: {err}");
+        }
+        let _ = app.emit("data-volume-restored", payload);
+      }
+    }
+  });
+}
+
+fn is_tracked(app: &AppHandle, pid: u32) -> bool {
+  let Some(state) = app.try_state::<BackendState>() else {
+    return false;
+  };
+  state
+    .lock()
+    .map(|guard| guard.as_ref().map(Child::id) == Some(pid))
+    .unwrap_or(false)
+}
@@ -0,0 +1,585 @@
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, AtomicU8, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tauri::image::Image;
+use tauri::{
+  AppHandle, Emitter, LogicalSize, Manager, Monitor, PhysicalPosition, State, WebviewWindow,
+};
+use tauri_plugin_dialog::{DialogExt, MessageDialogButtons, MessageDialogKind};
+
+use crate::dialogs::{self, OpenDialog};
+use crate::preferences::{self, Preferences};
+use crate::tasks;
+
+const MAX_ICON_FILE_BYTES: u64 = 5 * 1024 * 1024;
+const MAX_ICON_DIMENSION: u32 = 1024;
+/// Moves and resizes fire continuously during a drag; the frontend hears about them this often.
+const GEOMETRY_EVENT_INTERVAL: Duration = Duration::from_millis(100);
+/// Focus moving to one of our own dialogs or windows lands within this long after the blur.
+const HIDE_ON_BLUR_GRACE: Duration = Duration::from_millis(300);
+
+/// Bumped on every focus change so a pending hide-on-blur can tell it has gone stale.
+static FOCUS_GENERATION: AtomicU64 = AtomicU64::new(0);
+
+/// Shows a window that was kept hidden during startup. On macOS it fades in from fully
+/// transparent so the first paint doesn't pop in; elsewhere it is simply shown.
+pub fn show_fading_in(window: &WebviewWindow) {
+  #[cfg(target_os = "macos")]
+  {
+    let target = window.clone();
+    let scheduled = window.run_on_main_thread(move || {
+      use objc::{msg_send, sel, sel_impl};
+
+      let Some(ns_window) = crate::titlebar::ns_window_for(&target, "fade-in") else {
+        let _ = target.show();
+        return;
+      };
+      // SAFETY: main thread, live NSWindow; `animator` animates the alpha change over the
+      // default duration.
+      unsafe {
+        let _: () = msg_send![ns_window, setAlphaValue: 0.0f64];
+        let _ = target.show();
+        let animator: cocoa::base::id = msg_send![ns_window, animator];
+        let _: () = msg_send![animator, setAlphaValue: 1.0f64];
+      }
+    });
+    if scheduled.is_ok() {
+      return;
+    }
+  }
+  let _ = window.show();
+}
+
+/// What closing a window does; see [`set_close_behavior`].
+static CLOSE_BEHAVIOR: AtomicU8 = AtomicU8::new(CloseBehavior::Hide as u8);
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum CloseBehavior {
+  /// Hide to keep running in the background (the default).
+  Hide,
+  /// Ask whether to quit.
+  Prompt,
+  Quit,
+}
+
+impl CloseBehavior {
+  pub fn current() -> Self {
+    match CLOSE_BEHAVIOR.load(Ordering::SeqCst) {
+      value if value == Self::Prompt as u8 => Self::Prompt,
+      value if value == Self::Quit as u8 => Self::Quit,
+      _ => Self::Hide,
+    }
+  }
+}
+
+/// Whether any of the app's windows is showing. All windows share the one backend, so what it
+/// does while hidden waits for all of them.
+pub fn any_visible(app: &AppHandle) -> bool {
+  app
+    .webview_windows()
+    .values()
+    .any(|window| window.is_visible().unwrap_or(false))
+}
+
+/// With the `hide_on_blur` preference, hides the window shortly after it loses focus, like
+/// a popover, unless focus went to another of our windows or a native dialog meanwhile.
+pub fn hide_on_blur(window: &WebviewWindow, focused: bool) {
+  let generation = FOCUS_GENERATION.fetch_add(1, Ordering::SeqCst) + 1;
+  if focused
+    || !window
+      .state::<Preferences>()
+      .get_bool(preferences::HIDE_ON_BLUR, false)
+  {
+    return;
+  }
+  let window = window.clone();
+  tasks::spawn("hide-on-blur", move || {
+    std::thread::sleep(HIDE_ON_BLUR_GRACE);
+    if FOCUS_GENERATION.load(Ordering::SeqCst) != generation || dialogs::any_open() {
+      return;
+    }
+    let app = window.app_handle();
+    let app_focused = app
+      .webview_windows()
+      .values()
+      .any(|other| other.is_focused().unwrap_or(false));
+    if app_focused || !window.is_visible().unwrap_or(false) {
+      return;
+    }
+    let _ = window.hide();
+    crate::backend::stop_while_hidden(app);
+  });
+}
+
+/// A size bound in logical pixels.
+#[derive(Clone, Copy, Serialize, Deserialize)]
+pub struct SizeLimit {
+  pub width: f64,
+  pub height: f64,
+}
+
+#[derive(Clone, Copy, Serialize, Deserialize)]
+struct SizeConstraints {
+  min: Option<SizeLimit>,
+  max: Option<SizeLimit>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MonitorInfo {
+  /// Position in the list; what [`move_to_monitor`] takes.
+  pub index: usize,
+  pub name: Option<String>,
+  pub position: WindowPosition,
+  pub size: WindowSize,
+  pub scale_factor: f64,
+  pub primary: bool,
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WindowStateInfo {
+  pub visible: bool,
+  pub focused: bool,
+  pub maximized: bool,
+  pub minimized: bool,
+  pub fullscreen: bool,
+  pub always_on_top: bool,
+}
+
+impl WindowStateInfo {
+  pub fn of(window: &WebviewWindow) -> Self {
+    Self {
+      visible: window.is_visible().unwrap_or(false),
+      focused: window.is_focused().unwrap_or(false),
+      maximized: window.is_maximized().unwrap_or(false),
+      minimized: window.is_minimized().unwrap_or(false),
+      fullscreen: window.is_fullscreen().unwrap_or(false),
+      always_on_top: window.is_always_on_top().unwrap_or(false),
+    }
+  }
+}
+
+/// Pushes the current flags to the window's frontend so a custom titlebar can stay in sync.
+pub fn emit_state_changed(window: &WebviewWindow) {
+  let _ = window.emit_to(window.label(), "window-state-changed", WindowStateInfo::of(window));
+}
+
+/// Physical pixels, as reported by the window system.
+#[derive(Clone, Serialize)]
+pub struct WindowPosition {
+  pub x: i32,
+  pub y: i32,
+}
+
+/// Physical pixels of the content area.
+#[derive(Clone, Serialize)]
+pub struct WindowSize {
+  pub width: u32,
+  pub height: u32,
+}
+
+#[derive(Default)]
+struct ThrottleState {
+  last_run: Option<Instant>,
+  trailing_scheduled: bool,
+}
+
+#[derive(Debug, PartialEq)]
+enum ThrottleStep {
+  Run,
+  RunAfter(Duration),
+  Skip,
+}
+
+impl ThrottleState {
+  /// What a call at `now` should do, recorded as if it happens.
+  fn step(&mut self, now: Instant) -> ThrottleStep {
+    let elapsed = self.last_run.map(|last_run| now.saturating_duration_since(last_run));
+    match elapsed {
+      Some(elapsed) if elapsed < GEOMETRY_EVENT_INTERVAL => {
+        if self.trailing_scheduled {
+          return ThrottleStep::Skip;
+        }
+        self.trailing_scheduled = true;
+        ThrottleStep::RunAfter(GEOMETRY_EVENT_INTERVAL - elapsed)
+      }
+      _ => {
+        self.last_run = Some(now);
+        ThrottleStep::Run
+      }
+    }
+  }
+
+  fn trailing_ran(&mut self, now: Instant) {
+    self.last_run = Some(now);
+    self.trailing_scheduled = false;
+  }
+}
+
+/// Runs at most once per interval, plus one trailing run so the final state after a burst
+/// is never dropped. Each window event kind gets its own.
+#[derive(Clone, Default)]
+pub struct Throttle(Arc<Mutex<ThrottleState>>);
+
+impl Throttle {
+  pub fn run(&self, action: impl FnOnce() + Send + 'static) {
+    let Ok(mut state) = self.0.lock() else {
+      return;
+    };
+    match state.step(Instant::now()) {
+      ThrottleStep::Run => {
+        drop(state);
+        action();
+      }
+      ThrottleStep::RunAfter(delay) => {
+        let shared = self.0.clone();
+        std::thread::spawn(move || {
+          std::thread::sleep(delay);
+          if let Ok(mut state) = shared.lock() {
+            state.trailing_ran(Instant::now());
+          }
+          action();
+        });
+      }
+      ThrottleStep::Skip => {}
+    }
+  }
+}
+
+/// Emits `window-moved` with the position at the time of emission, so a trailing emit
+/// carries where the drag ended.
+pub fn emit_moved(window: &WebviewWindow) {
+  if let Ok(position) = window.outer_position() {
+    let payload = WindowPosition {
+      x: position.x,
+      y: position.y,
+    };
+    let _ = window.emit_to(window.label(), "window-moved", payload);
+  }
+}
+
+pub fn emit_resized(window: &WebviewWindow) {
+  if let Ok(size) = window.inner_size() {
+    let payload = WindowSize {
+      width: size.width,
+      height: size.height,
+    };
+    let _ = window.emit_to(window.label(), "window-resized", payload);
+  }
+}
+
+#[tauri::command]
+pub fn window_state(window: WebviewWindow) -> WindowStateInfo {
+  WindowStateInfo::of(&window)
+}
+
+#[tauri::command]
+pub fn minimize_window(window: WebviewWindow) -> Result<(), String> {
+  window.minimize().map_err(|err| err.to_string())
+}
+
+#[tauri::command]
+pub fn toggle_maximize_window(window: WebviewWindow) -> Result<(), String> {
+  let result = if window.is_maximized().map_err(|err| err.to_string())? {
+    window.unmaximize()
+  } else {
+    window.maximize()
+  };
+  result.map_err(|err| err.to_string())
+}
+
+/// Sets the window's minimum and maximum size for the current view, e.g. a compact mode.
+/// An omitted bound falls back to the window's configured one (minWidth/minHeight in
+/// tauri.conf.json). With `persist`, the constraints are re-applied on the next launch;
+/// without it, any saved ones are cleared.
+#[tauri::command]
+pub fn set_size_constraints(
+  window: WebviewWindow,
+  preferences: State<'_, Preferences>,
+  min: Option<SizeLimit>,
+  max: Option<SizeLimit>,
+  persist: Option<bool>,
+) -> Result<(), String> {
+  let constraints = SizeConstraints { min, max };
+  for limit in [min, max].into_iter().flatten() {
+    if !(limit.width.is_finite() && limit.height.is_finite())
+      || limit.width <= 0.0
+      || limit.height <= 0.0
+    {
+      return Err(format!("Invalid size {} x {}", limit.width, limit.height));
+    }
+  }
+  if let (Some(min), Some(max)) = (min, max) {
+    if min.width > max.width || min.height > max.height {
+      return Err("The minimum size must not exceed the maximum size".to_string());
+    }
+  }
+  apply_size_constraints(&window, constraints)?;
+  if persist.unwrap_or(false) {
+    let value = serde_json::to_value(constraints).map_err(|err| err.to_string())?;
+    preferences.set(preferences::SIZE_CONSTRAINTS, value)?;
+  } else if preferences
+    .get(preferences::SIZE_CONSTRAINTS)
+    .is_some_and(|saved| !saved.is_null())
+  {
+    preferences.set(preferences::SIZE_CONSTRAINTS, Value::Null)?;
+  }
+  Ok(())
+}
+
+/// Re-applies constraints persisted by [`set_size_constraints`].
+pub fn restore_size_constraints(window: &WebviewWindow) {
+  let Some(value) = window
+    .state::<Preferences>()
+    .get(preferences::SIZE_CONSTRAINTS)
+    .filter(|value| !value.is_null())
+  else {
+    return;
+  };
+  let constraints = match serde_json::from_value::<SizeConstraints>(value) {
+    Ok(constraints) => constraints,
+    Err(err) => {
+      log::warn!("ignoring malformed saved size constraints: {err}");
+      return;
+    }
+  };
+  if let Err(err) = apply_size_constraints(window, constraints) {
+    log::warn!("failed to restore size constraints: {err}");
+  }
+}
+
+fn apply_size_constraints(
+  window: &WebviewWindow,
+  constraints: SizeConstraints,
+) -> Result<(), String> {
+  let configured = window
+    .app_handle()
+    .config()
+    .app
+    .windows
+    .iter()
+    .find(|config| config.label == window.label())
+    .cloned();
+  let default_min = configured.as_ref().and_then(|config| {
+    Some(SizeLimit {
+      width: config.min_width?,
+      height: config.min_height?,
+    })
+  });
+  let default_max = configured.as_ref().and_then(|config| {
+    Some(SizeLimit {
+      width: config.max_width?,
+      height: config.max_height?,
+    })
+  });
+  let logical = |limit: SizeLimit| LogicalSize::new(limit.width, limit.height);
+  window
+    .set_min_size(constraints.min.or(default_min).map(logical))
+    .and_then(|()| window.set_max_size(constraints.max.or(default_max).map(logical)))
+    .map_err(|err| format!("Failed to set size constraints: {err}"))
+}
+
+/// Asks whether to quit, for a close while the frontend has set the `prompt` behavior.
+pub fn confirm_quit(window: &WebviewWindow) {
+  let app = window.app_handle().clone();
+  let open = OpenDialog::track();
+  window
+    .dialog()
+    .message("Quit Pluto Duck? Unsaved changes may be lost.")
+    .title("Pluto Duck")
+    .kind(MessageDialogKind::Warning)
+    .parent(window)
+    .buttons(MessageDialogButtons::OkCancelCustom("Quit".to_string(), "Cancel".to_string()))
+    .show(move |quit| {
+      drop(open);
+      if quit {
+        app.exit(0);
+      }
+    });
+}
+
+/// Changes what closing a window does while the frontend is in a state that needs it, e.g.
+/// `prompt` during unsaved changes: `hide` (the default), `prompt` or `quit`.
+#[tauri::command]
+pub fn set_close_behavior(behavior: String) -> Result<(), String> {
+  let behavior = match behavior.trim().to_ascii_lowercase().as_str() {
+    "hide" => CloseBehavior::Hide,
+    "prompt" => CloseBehavior::Prompt,
+    "quit" => CloseBehavior::Quit,
+    other => {
+      return Err(format!(
+        "Unknown close behavior {other:?}; expected hide, prompt or quit"
+      ))
+    }
+  };
+  CLOSE_BEHAVIOR.store(behavior as u8, Ordering::SeqCst);
+  Ok(())
+}
+
+/// The connected displays, in the OS's order.
+#[tauri::command]
+pub fn list_monitors(window: WebviewWindow) -> Result<Vec<MonitorInfo>, String> {
+  let primary = window.primary_monitor().ok().flatten();
+  let monitors = window.available_monitors().map_err(|err| err.to_string())?;
+  Ok(
+    monitors
+      .iter()
+      .enumerate()
+      .map(|(index, monitor)| MonitorInfo {
+        index,
+        name: monitor.name().cloned(),
+        position: WindowPosition {
+          x: monitor.position().x,
+          y: monitor.position().y,
+        },
+        size: WindowSize {
+          width: monitor.size().width,
+          height: monitor.size().height,
+        },
+        scale_factor: monitor.scale_factor(),
+        primary: primary.as_ref().is_some_and(|primary| same_monitor(primary, monitor)),
+      })
+      .collect(),
+  )
+}
+
+/// Moves the window onto a display from [`list_monitors`], centered, and optionally
+/// maximizes it there. The display is remembered by name for the next launch.
+#[tauri::command]
+pub fn move_to_monitor(
+  window: WebviewWindow,
+  preferences: State<'_, Preferences>,
+  monitor_index: usize,
+  maximize: Option<bool>,
+) -> Result<(), String> {
+  let monitors = window.available_monitors().map_err(|err| err.to_string())?;
+  // The list may have changed since the frontend fetched it.
+  let monitor = monitors.get(monitor_index).ok_or_else(|| {
+    format!("Monitor {monitor_index} is not connected; refresh the monitor list")
+  })?;
+  place_on_monitor(&window, monitor, maximize.unwrap_or(false))?;
+  let name = monitor.name().cloned().unwrap_or_default();
+  preferences.set(preferences::MONITOR, Value::String(name))
+}
+
+/// Reopens the window on the display chosen with [`move_to_monitor`] if it is still connected.
+pub fn restore_monitor(window: &WebviewWindow) {
+  let Some(name) = window
+    .state::<Preferences>()
+    .get(preferences::MONITOR)
+    .and_then(|value| value.as_str().map(str::to_string))
+    .filter(|name| !name.is_empty())
+  else {
+    return;
+  };
+  let monitors = window.available_monitors().unwrap_or_default();
+  let Some(monitor) = monitors.iter().find(|monitor| monitor.name() == Some(&name)) else {
+    log::info!("saved monitor {name} is not connected; keeping the default placement");
+    return;
+  };
+  if let Err(err) = place_on_monitor(window, monitor, false) {
+    log::warn!("failed to move window to monitor {name}: {err}");
+  }
+}
+
+fn same_monitor(a: &Monitor, b: &Monitor) -> bool {
+  a.name() == b.name() && a.position() == b.position()
+}
+
+fn place_on_monitor(
+  window: &WebviewWindow,
+  monitor: &Monitor,
+  maximize: bool,
+) -> Result<(), String> {
+  if window.is_maximized().unwrap_or(false) {
+    window.unmaximize().map_err(|err| err.to_string())?;
+  }
+  let area = monitor.size();
+  let origin = monitor.position();
+  let size = window.outer_size().map_err(|err| err.to_string())?;
+  let offset = |available: u32, used: u32| i32::try_from(available.saturating_sub(used) / 2);
+  let position = PhysicalPosition::new(
+    origin.x + offset(area.width, size.width).unwrap_or(0),
+    origin.y + offset(area.height, size.height).unwrap_or(0),
+  );
+  window
+    .set_position(position)
+    .map_err(|err| format!("Failed to move window: {err}"))?;
+  if maximize {
+    window.maximize().map_err(|err| err.to_string())?;
+  }
+  Ok(())
+}
+
+/// Requests a close so it goes through the same `CloseRequested` handling as the native
+/// button (hide-on-close) rather than destroying the window.
+#[tauri::command]
+pub fn close_window(window: WebviewWindow) -> Result<(), String> {
+  window.close().map_err(|err| err.to_string())
+}
+
+/// Wipes the webview's cache, cookies and storage (localStorage, IndexedDB) and reloads, for
+/// a stuck UI caused by stale frontend state. Backend data in DuckDB is left untouched.
+#[tauri::command]
+pub async fn clear_webview_cache(window: WebviewWindow) -> Result<(), String> {
+  window
+    .clear_all_browsing_data()
+    .map_err(|err| format!("Failed to clear webview data: {err}"))?;
+  log::info!("cleared browsing data for window {}; reloading", window.label());
+  window.reload().map_err(|err| format!("Failed to reload window: {err}"))
+}
+
+/// Gives a window its own PNG or ICO icon so workspace windows can be told apart in the
+/// taskbar. macOS has a single app-wide Dock icon, so this is a no-op there.
+#[tauri::command]
+pub fn set_window_icon(window: WebviewWindow, path: PathBuf) -> Result<(), String> {
+  let extension = path
+    .extension()
+    .and_then(|extension| extension.to_str())
+    .map(str::to_ascii_lowercase);
+  if !matches!(extension.as_deref(), Some("png" | "ico")) {
+    return Err("Window icons must be PNG or ICO files".to_string());
+  }
+  let file_size = std::fs::metadata(&path)
+    .map_err(|err| format!("Failed to read {}: {err}", path.display()))?
+    .len();
+  if file_size > MAX_ICON_FILE_BYTES {
+    return Err(format!("Icon file is too large ({file_size} bytes)"));
+  }
+
+  let icon = Image::from_path(&path)
+    .map_err(|err| format!("Failed to load icon {}: {err}", path.display()))?;
+  let (width, height) = (icon.width(), icon.height());
+  if width == 0 || height == 0 || width > MAX_ICON_DIMENSION || height > MAX_ICON_DIMENSION {
+    return Err(format!(
+      "Icon must be between 1x1 and {MAX_ICON_DIMENSION}x{MAX_ICON_DIMENSION} pixels, got {width}x{height}"
+    ));
+  }
+  window.set_icon(icon).map_err(|err| err.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn throttle_coalesces_a_burst_into_one_leading_and_one_trailing_run() {
+    let start = Instant::now();
+    let ms = Duration::from_millis;
+    let mut state = ThrottleState::default();
+    assert_eq!(state.step(start), ThrottleStep::Run);
+    assert_eq!(state.step(start + ms(10)), ThrottleStep::RunAfter(ms(90)));
+    assert_eq!(state.step(start + ms(20)), ThrottleStep::Skip);
+    assert_eq!(state.step(start + ms(90)), ThrottleStep::Skip);
+
+    state.trailing_ran(start + ms(100));
+    assert_eq!(state.step(start + ms(150)), ThrottleStep::RunAfter(ms(50)));
+    state.trailing_ran(start + ms(200));
+    assert_eq!(state.step(start + ms(300)), ThrottleStep::Run);
+  }
+}
@@ -0,0 +1,218 @@
+//! Extra backend processes (e.g. a background job runner) declared under `workers` in the env
+//! config. Each runs alongside the web server with its own command, environment and log files,
+//! is restarted with backoff when it exits unexpectedly, and stops together with the backend.
+//! Workers find the web server through `PLUTODUCK_BACKEND_URL`.
+
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::process::{Child, Command, Stdio};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use anyhow::{bail, Context, Result};
+use log::{error, info, warn};
+use serde::Deserialize;
+use tauri::{AppHandle, Manager};
+
+use crate::config::EnvConfig;
+use crate::{backend, logs, secrets, tasks};
+
+const SUPERVISE_INTERVAL: Duration = Duration::from_secs(1);
+const MIN_RESTART_DELAY: Duration = Duration::from_secs(1);
+const MAX_RESTART_DELAY: Duration = Duration::from_secs(60);
+/// A worker that stayed up this long is considered healthy again and restarts without delay.
+const STABLE_UPTIME: Duration = Duration::from_secs(60);
+
+#[derive(Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkerConfig {
+  /// Identifies the worker in logs and file names; letters, digits, `-` and `_`.
+  pub name: String,
+  /// Executable to run; relative paths resolve against the backend binary's directory.
+  /// Defaults to the backend binary itself, so `args` can select a subcommand.
+  #[serde(default)]
+  pub command: Option<PathBuf>,
+  #[serde(default)]
+  pub args: Vec<String>,
+  #[serde(default)]
+  pub env: HashMap<String, String>,
+}
+
+/// Running workers by name. A stop takes the child out first, which ends its supervisor.
+#[derive(Default)]
+pub struct WorkerState(Mutex<HashMap<String, Child>>);
+
+/// Starts every configured worker and its supervisor; failures are logged per worker so one
+/// broken entry doesn't keep the app from starting.
+pub fn launch(app: &AppHandle) {
+  for config in configured(app) {
+    if let Err(err) = start(app, &config) {
+      error!("failed to start worker {}: {err:#}", config.name);
+    }
+  }
+}
+
+/// Stops all workers, e.g. on exit or before the data root is touched.
+pub fn stop_all(app: &AppHandle) {
+  let Some(state) = app.try_state::<WorkerState>() else {
+    return;
+  };
+  let children: Vec<(String, Child)> = match state.0.lock() {
+    Ok(mut workers) => workers.drain().collect(),
+    Err(_) => return,
+  };
+  for (name, mut child) in children {
+    info!("stopping worker {name}");
+    backend::stop_child(&mut child);
+  }
+}
+
+/// Pids of the workers currently running.
+pub fn pids(app: &AppHandle) -> Vec<u32> {
+  let Some(state) = app.try_state::<WorkerState>() else {
+    return Vec::new();
+  };
+  state
+    .0
+    .lock()
+    .map(|workers| workers.values().map(Child::id).collect())
+    .unwrap_or_default()
+}
+
+fn configured(app: &AppHandle) -> Vec<WorkerConfig> {
+  app
+    .state::<EnvConfig>()
+    .workers
+    .iter()
+    .filter(|config| {
+      let valid = !config.name.is_empty()
+        && config
+          .name
+          .bytes()
+          .all(|byte| byte.is_ascii_alphanumeric() || byte == b'-' || byte == b'_');
+      if !valid {
+        warn!("ignoring worker with invalid name {:?}", config.name);
+      }
+      valid
+    })
+    .cloned()
+    .collect()
+}
+
+fn start(app: &AppHandle, config: &WorkerConfig) -> Result<()> {
+  let state = app.state::<WorkerState>();
+  let mut workers = state
+    .0
+    .lock()
+    .map_err(|_| anyhow::anyhow!("worker state lock is poisoned"))?;
+  if workers.contains_key(&config.name) {
+    bail!("a worker named {} is already running", config.name);
+  }
+  let child = spawn(app, config)?;
+  let pid = child.id();
+  workers.insert(config.name.clone(), child);
+  supervise(app.clone(), config.clone(), pid);
+  Ok(())
+}
+
+fn spawn(app: &AppHandle, config: &WorkerConfig) -> Result<Child> {
+  let backend_binary = backend::backend_binary_path(app)?;
+  let backend_dir = backend_binary
+    .parent()
+    .map(PathBuf::from)
+    .unwrap_or_default();
+  let program = match &config.command {
+    Some(command) if command.is_relative() => backend_dir.join(command),
+    Some(command) => command.clone(),
+    None => backend_binary.clone(),
+  };
+
+  let data_root = backend::resolve_data_root(app);
+  let log_dir = logs::log_dir(&data_root);
+  let log_path = |stream: &str| log_dir.join(format!("worker-{}-{stream}.log", config.name));
+  let append = logs::append_logs(app);
+  let stdout_log = logs::open_session_log(&log_path("stdout"), append)
+    .context("failed to create worker stdout log")?;
+  let stderr_log = logs::open_session_log(&log_path("stderr"), append)
+    .context("failed to create worker stderr log")?;
+
+  let env_config = app.state::<EnvConfig>();
+  let secrets_file =
+    secrets::write_for_worker(&data_root, &config.name, &env_config.backend_secrets)?;
+  let mut command = Command::new(&program);
+  command
+    .current_dir(&backend_dir)
+    .envs(&env_config.backend_env)
+    .envs(&config.env)
+    .env("PLUTODUCK_DATA_DIR__ROOT", &data_root)
+    .env("PLUTODUCK_BACKEND_URL", backend::origin())
+    .args(&config.args)
+    .stdout(Stdio::from(stdout_log))
+    .stderr(Stdio::from(stderr_log));
+  if let Some(path) = &secrets_file {
+    command.env(secrets::SECRETS_FILE_ENV, path);
+  }
+  let child = command
+    .spawn()
+    .with_context(|| format!("failed to spawn {}", program.display()))?;
+  info!("worker {} started ({}, pid {})", config.name, program.display(), child.id());
+  Ok(child)
+}
+
+/// Restarts the worker whenever it exits while still tracked, doubling the delay on quick
+/// repeated failures up to a minute.
+fn supervise(app: AppHandle, config: WorkerConfig, pid: u32) {
+  let name = format!("worker-supervisor-{}", config.name);
+  tasks::spawn(&name, move || {
+    let mut pid = pid;
+    let mut started = Instant::now();
+    let mut delay = MIN_RESTART_DELAY;
+    loop {
+      std::thread::sleep(SUPERVISE_INTERVAL);
+      let state = app.state::<WorkerState>();
+      let status = {
+        let Ok(mut workers) = state.0.lock() else {
+          return;
+        };
+        match workers.get_mut(&config.name) {
+          Some(child) if child.id() == pid => match child.try_wait() {
+            Ok(None) => continue,
+            Ok(Some(status)) => status,
+            Err(_) => return,
+          },
+          _ => return,
+        }
+      };
+
+      if started.elapsed() >= STABLE_UPTIME {
+        delay = MIN_RESTART_DELAY;
+      }
+      warn!(
+        "worker {} exited with {status}; restarting in {delay:?}",
+        config.name
+      );
+      std::thread::sleep(delay);
+      delay = (delay * 2).min(MAX_RESTART_DELAY);
+
+      let Ok(mut workers) = state.0.lock() else {
+        return;
+      };
+      // Stopped on purpose while we were waiting.
+      if !matches!(workers.get(&config.name), Some(child) if child.id() == pid) {
+        return;
+      }
+      match spawn(&app, &config) {
+        Ok(child) => {
+          pid = child.id();
+          started = Instant::now();
+          workers.insert(config.name.clone(), child);
+        }
+        Err(err) => {
+          error!("failed to restart worker {}: {err:#}", config.name);
+          workers.remove(&config.name);
+          return;
+        }
+      }
+    }
+  });
+}